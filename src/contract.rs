@@ -1,30 +1,50 @@
+use cosmwasm_std::from_slice;
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Decimal, Decimal256, Deps, DepsMut, Env, Fraction, MessageInfo,
-    Order, Response, StdResult, Timestamp, Uint128, Uint256,
+    attr, coins, entry_point, to_binary, to_vec, Addr, Attribute, BankMsg, Binary, CosmosMsg,
+    Decimal, Decimal256, Deps, DepsMut, Empty, Env, Fraction, MessageInfo, Order, Response,
+    StdError, StdResult, Storage, Timestamp, Uint128, Uint256, WasmMsg,
 };
-use cosmwasm_std::{from_slice, CosmosMsg};
 use cw0::maybe_addr;
+use cw_storage_plus::Bound;
 
-use cw20::Cw20ReceiveMsg;
-use cw_asset::Asset;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_asset::{Asset, AssetInfo, AssetInfoUnchecked, AssetList};
+use cw_utils::{must_pay, one_coin, Expiration};
 
 use crate::helper::{days_to_seconds, get_decimals};
 use crate::msg::{
-    ClaimResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, ListClaimsResponse, MigrateMsg,
-    QueryMsg, ReceiveMsg, StakerForAllDurationResponse, StakerResponse, StateResponse,
+    AllPositionsResponse, AllStakersResponse, ClaimResponse, ClaimStatus, ClaimsSummary,
+    ConfigResponse, ExecuteMsg, ForceClaimFeeResponse, ForceClaimSimulationResponse,
+    InstantiateMsg, ListClaimsResponse, MigrateMsg, PendingAdminResponse, PendingChangeResponse,
+    PendingChangesResponse, PendingRewardsResponse, PositionPendingRewardsResponse,
+    PositionRewardResponse, QueryMsg, ReceiveMsg, RewardRunwayResponse, RewardScheduleResponse,
+    RewardSchedulesResponse, RewardStateResponse, RoleMembersResponse, SimulateForceClaimResponse,
+    StakerForAllDurationResponse, StakerResponse, StakerSummaryResponse, StateResponse,
 };
 use crate::state::{
-    CW20Balance, Claim, Claims, Config, StakePosition, State, CLAIMS_KEY, CONFIG, STAKERS, STATE,
+    has_role, load_owned_position, load_owner_positions, next_change_id, next_position_id,
+    remove_position, save_position, stakers, Balance, Change, Claim, Claims, Config, ConfigUpdate,
+    FeeCurve, PauseFlags, PositionReward, QueuedChange, RewardSchedule, RewardState, Role,
+    StakePosition, State, CLAIMS_KEY, CLAIM_TOTALS, CONFIG, MAX_REWARD_SCHEDULES,
+    MAX_REWARD_STREAMS, OWNER_SUMMARIES, PENDING_ADMIN, PENDING_ADMIN_EXPIRY, QUEUED_CHANGES,
+    ROLES, STATE,
 };
 use crate::ContractError;
 use cosmwasm_std;
+use std::collections::BTreeMap;
 
 use std::convert::TryInto;
 use std::ops::Add;
 
+// pagination limits of list queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+// remaining mature claims are counted up to this after ClaimUnbonded
+const MAX_REMAINING_COUNT: usize = 100;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
@@ -33,10 +53,10 @@ pub fn instantiate(
     let admin = maybe_addr(deps.api, msg.admin)?.unwrap_or_else(|| info.sender.clone());
     // validate fee_collector address
     let fee_collector_address = deps.api.addr_validate(&msg.fee_collector)?;
-    // validate stake_token_address
-    let stake_token_address = deps.api.addr_validate(&msg.stake_token_address)?;
-    // validate reward_token_address
-    let reward_token_address = deps.api.addr_validate(&msg.reward_token_address)?;
+    // validate stake_token
+    let stake_token = msg.stake_token.check(deps.api, None)?;
+    // validate reward_token
+    let reward_token = msg.reward_token.check(deps.api, None)?;
     // validate max_bond_duration
     if msg.max_bond_duration < 1 {
         return Err(ContractError::InvalidMaxBondDuration {});
@@ -44,37 +64,52 @@ pub fn instantiate(
     if (msg.force_claim_ratio < Decimal::zero()) || (msg.force_claim_ratio >= Decimal::one()) {
         return Err(ContractError::InvalidForceClaimRatio {});
     }
+    let force_claim_curve = msg.force_claim_curve.unwrap_or_default();
+    force_claim_curve.validate()?;
+    let fee_pool_ratio = msg.fee_pool_ratio.unwrap_or_default();
+    if fee_pool_ratio > Decimal::one() {
+        return Err(ContractError::InvalidFeePoolRatio {});
+    }
+    let fee_burn_ratio = msg.fee_burn_ratio.unwrap_or_default();
+    validate_fee_burn_ratio(fee_burn_ratio, fee_pool_ratio)?;
 
     let config = Config {
         admin: admin.clone(),
-        stake_token_address,
-        reward_token_address,
+        stake_token,
+        reward_token,
         force_claim_ratio: msg.force_claim_ratio,
+        force_claim_curve,
+        fee_pool_ratio,
+        fee_burn_ratio,
         fee_collector: fee_collector_address,
         max_bond_duration: msg.max_bond_duration,
-        reward_per_second: Uint128::zero(),
+        pause: PauseFlags::default(),
+        emergency: false,
+        timelock_delay: msg.timelock_delay.unwrap_or_default(),
     };
     CONFIG.save(deps.storage, &config)?;
-    //set state
+    PENDING_ADMIN.set(deps.branch(), None)?;
+    //set state with reward stream of the reward token
+    let mut rewards = BTreeMap::new();
+    rewards.insert(
+        config.reward_token.to_string(),
+        RewardState::new(config.reward_token.clone()),
+    );
     let state = State {
-        global_index: Decimal256::zero(),
         total_staked: Uint128::zero(),
         total_weight: Decimal256::zero(),
-        total_reward_claimed: Uint128::zero(),
         last_updated: env.block.time,
+        rewards,
+        total_fees_burned: Uint128::zero(),
+        total_fees_collected: Uint128::zero(),
+        rewards_frozen: false,
     };
     STATE.save(deps.storage, &state)?;
     let res = Response::default()
         .add_attribute("method", "instantiate")
         .add_attribute("admin", admin)
-        .add_attribute(
-            "stake_token_address",
-            config.stake_token_address.to_string(),
-        )
-        .add_attribute(
-            "reward_token_address",
-            config.reward_token_address.to_string(),
-        )
+        .add_attribute("stake_token", config.stake_token.to_string())
+        .add_attribute("reward_token", config.reward_token.to_string())
         .add_attribute("force_claim_ratio", config.force_claim_ratio.to_string())
         .add_attribute("fee_collector", config.fee_collector);
     Ok(res)
@@ -89,24 +124,90 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Receive(receive_message) => execute_receive(deps, env, info, receive_message),
-        ExecuteMsg::UpdateRewardIndex {} => execute_update_reward_index(deps, env),
-        ExecuteMsg::UpdateStakerRewards { address } => {
-            execute_update_staker_rewards(deps, env, info, address)
+        ExecuteMsg::Bond { duration_day } => execute_bond_native(deps, env, info, duration_day),
+        ExecuteMsg::RewardUpdate { reward_end_date } => {
+            execute_reward_update_native(deps, env, info, reward_end_date)
         }
+        ExecuteMsg::UpdateRewardIndex {} => execute_update_reward_index(deps, env),
+        ExecuteMsg::UpdateStakerRewards {
+            address,
+            position_id,
+        } => execute_update_staker_rewards(deps, env, info, address, position_id),
         ExecuteMsg::UnbondStake {
             amount,
+            position_id,
+        } => execute_unbond(deps, env, info, amount, position_id),
+        ExecuteMsg::ClaimUnbonded { release_at, limit } => {
+            execute_claim(deps, env, info, release_at, limit)
+        }
+        ExecuteMsg::ReceiveReward { position_id } => {
+            execute_receive_reward(deps, env, info, position_id)
+        }
+        ExecuteMsg::Compound { position_id } => execute_compound(deps, env, info, position_id),
+        ExecuteMsg::SetAutoCompound {
+            position_id,
+            auto_compound,
+        } => execute_set_auto_compound(deps, info, position_id, auto_compound),
+        ExecuteMsg::ExtendLock {
+            position_id,
+            to_duration,
+            amount,
+        } => execute_extend_lock(deps, env, info, position_id, to_duration, amount),
+        ExecuteMsg::Rebond {
+            release_at,
             duration_as_days,
-        } => execute_unbond(deps, env, info, amount, duration_as_days),
-        ExecuteMsg::ClaimUnbonded {} => execute_claim(deps, env, info),
-        ExecuteMsg::ReceiveReward {} => execute_receive_reward(deps, env, info),
+        } => execute_rebond(deps, env, info, release_at, duration_as_days),
         ExecuteMsg::UpdateConfig {
-            admin,
             fee_collector,
             force_claim_ratio,
-        } => execute_update_config(deps, env, info, force_claim_ratio, fee_collector, admin),
-        ExecuteMsg::ForceClaim { release_at } => execute_force_claim(deps, env, info, release_at),
-        ExecuteMsg::SetRewardPerSecond { reward_per_second } => {
-            execute_set_reward_per_second(deps, env, info, reward_per_second)
+            force_claim_curve,
+            fee_pool_ratio,
+            fee_burn_ratio,
+            max_bond_duration,
+        } => execute_update_config(
+            deps,
+            env,
+            info,
+            force_claim_ratio,
+            force_claim_curve,
+            fee_pool_ratio,
+            fee_burn_ratio,
+            fee_collector,
+            max_bond_duration,
+        ),
+        ExecuteMsg::ProposeNewAdmin { new_admin, expires } => {
+            execute_propose_new_admin(deps, env, info, new_admin, expires)
+        }
+        ExecuteMsg::AcceptAdmin {} => execute_accept_admin(deps, env, info),
+        ExecuteMsg::CancelAdminTransfer {} => execute_cancel_admin_transfer(deps, info),
+        ExecuteMsg::SetTimelockDelay { timelock_delay } => {
+            execute_set_timelock_delay(deps, env, info, timelock_delay)
+        }
+        ExecuteMsg::ExecuteChange { change_id } => execute_change(deps, env, change_id),
+        ExecuteMsg::CancelChange { change_id } => execute_cancel_change(deps, info, change_id),
+        ExecuteMsg::GrantRole { role, address } => execute_grant_role(deps, info, role, address),
+        ExecuteMsg::RevokeRole { role, address } => execute_revoke_role(deps, info, role, address),
+        ExecuteMsg::SetPause {
+            bonding,
+            rewards,
+            unbonding,
+        } => execute_set_pause(deps, env, info, bonding, rewards, unbonding),
+        ExecuteMsg::SetEmergency { emergency } => execute_set_emergency(deps, env, info, emergency),
+        ExecuteMsg::EmergencyWithdraw { limit } => {
+            execute_emergency_withdraw(deps, env, info, limit)
+        }
+        ExecuteMsg::ForceClaim { release_at, amount } => {
+            execute_force_claim(deps, env, info, release_at, amount)
+        }
+        ExecuteMsg::SetRewardPerSecond {
+            reward_per_second,
+            reward_token,
+        } => execute_set_reward_per_second(deps, env, info, reward_per_second, reward_token),
+        ExecuteMsg::AddRewardStream { reward_token } => {
+            execute_add_reward_stream(deps, env, info, reward_token)
+        }
+        ExecuteMsg::ScheduleReward { start, end } => {
+            execute_schedule_reward_native(deps, env, info, start, end)
         }
     }
 }
@@ -120,25 +221,195 @@ pub fn execute_receive(
 ) -> Result<Response, ContractError> {
     let msg = from_slice::<ReceiveMsg>(&wrapper.msg)?;
     let api = deps.api;
-    let balance = CW20Balance {
-        denom: info.sender,
+    let balance = Balance {
+        denom: AssetInfo::cw20(info.sender),
         amount: wrapper.amount,
         sender: api.addr_validate(&wrapper.sender)?,
     };
     match msg {
         ReceiveMsg::Bond { duration_day } => execute_bond(deps, env, balance, duration_day),
+        ReceiveMsg::RewardUpdate { reward_end_date } => {
+            execute_reward_update(deps, env, balance, reward_end_date)
+        }
+        ReceiveMsg::ScheduleReward { start, end } => {
+            execute_schedule_reward(deps, env, balance, start, end)
+        }
+    }
+}
+
+pub fn execute_reward_update_native(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    reward_end_date: Timestamp,
+) -> Result<Response, ContractError> {
+    let fund = one_coin(&info)?;
+    let balance = Balance {
+        denom: AssetInfo::native(fund.denom),
+        amount: fund.amount,
+        sender: info.sender,
+    };
+    execute_reward_update(deps, env, balance, reward_end_date)
+}
+
+pub fn execute_reward_update(
+    deps: DepsMut,
+    env: Env,
+    balance: Balance,
+    reward_end_date: Timestamp,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    // only reward manager can fund rewards
+    assert_role(deps.storage, &config, Role::RewardManager, &balance.sender)?;
+    if balance.amount.is_zero() {
+        return Err(ContractError::NoFund {});
+    }
+    if reward_end_date <= env.block.time {
+        return Err(ContractError::InvalidRewardEndTime {});
+    }
+    let mut state = STATE.load(deps.storage)?;
+    // distribute rewards with the old rate before changing it
+    update_reward_index(&mut state, env.block.time)?;
+    // check denom
+    let reward_state = state
+        .rewards
+        .get_mut(&balance.denom.to_string())
+        .ok_or(ContractError::RewardStreamNotFound {})?;
+
+    // rewards that are funded but not distributed yet. scheduled rewards are excluded
+    let total_rewards = reward_state
+        .unscheduled_rewards()?
+        .checked_add(balance.amount)?;
+    // reward per second = (remaining + incoming) / (end - now)
+    let reward_per_second = total_rewards.checked_div(Uint128::from(
+        reward_end_date.seconds() - env.block.time.seconds(),
+    ))?;
+    if reward_per_second.is_zero() {
+        return Err(ContractError::InvalidRewardPerSecond {});
+    }
+    // lowering the rate of a running stream is left to the timelocked SetRewardPerSecond
+    let running = match reward_state.reward_end_time {
+        Some(end_time) => end_time > env.block.time,
+        None => true,
+    };
+    if config.timelock_delay > 0 && running && reward_per_second < reward_state.reward_per_second {
+        return Err(ContractError::RewardRateLowered {});
+    }
+
+    reward_state.total_reward_supply = reward_state
+        .total_reward_supply
+        .checked_add(balance.amount)?;
+    reward_state.remaining_rewards = reward_state.remaining_rewards.checked_add(balance.amount)?;
+    reward_state.reward_per_second = reward_per_second;
+    reward_state.reward_end_time = Some(reward_end_date);
+    STATE.save(deps.storage, &state)?;
+
+    let res = Response::new()
+        .add_attribute("action", "reward_update")
+        .add_attribute("reward_token", balance.denom.to_string())
+        .add_attribute("amount", balance.amount)
+        .add_attribute("total_rewards", total_rewards)
+        .add_attribute("reward_per_second", reward_per_second)
+        .add_attribute("reward_end_date", reward_end_date.to_string());
+    Ok(res)
+}
+
+pub fn execute_schedule_reward_native(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start: Timestamp,
+    end: Timestamp,
+) -> Result<Response, ContractError> {
+    let fund = one_coin(&info)?;
+    let balance = Balance {
+        denom: AssetInfo::native(fund.denom),
+        amount: fund.amount,
+        sender: info.sender,
+    };
+    execute_schedule_reward(deps, env, balance, start, end)
+}
+
+pub fn execute_schedule_reward(
+    deps: DepsMut,
+    env: Env,
+    balance: Balance,
+    start: Timestamp,
+    end: Timestamp,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    // only reward manager can fund rewards
+    assert_role(deps.storage, &config, Role::RewardManager, &balance.sender)?;
+    if balance.amount.is_zero() {
+        return Err(ContractError::NoFund {});
+    }
+    if start < env.block.time || end <= start {
+        return Err(ContractError::InvalidRewardSchedule {});
+    }
+    let mut state = STATE.load(deps.storage)?;
+    update_reward_index(&mut state, env.block.time)?;
+    // check denom
+    let reward_state = state
+        .rewards
+        .get_mut(&balance.denom.to_string())
+        .ok_or(ContractError::RewardStreamNotFound {})?;
+    if reward_state.schedules.len() >= MAX_REWARD_SCHEDULES {
+        return Err(ContractError::TooManyRewardSchedules {});
     }
+
+    reward_state.schedules.push(RewardSchedule {
+        start,
+        end,
+        amount: balance.amount,
+        distributed: Uint128::zero(),
+    });
+    reward_state.total_reward_supply = reward_state
+        .total_reward_supply
+        .checked_add(balance.amount)?;
+    reward_state.remaining_rewards = reward_state.remaining_rewards.checked_add(balance.amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    let res = Response::new()
+        .add_attribute("action", "schedule_reward")
+        .add_attribute("reward_token", balance.denom.to_string())
+        .add_attribute("amount", balance.amount)
+        .add_attribute("start", start.to_string())
+        .add_attribute("end", end.to_string());
+    Ok(res)
+}
+
+pub fn execute_bond_native(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    duration: u128,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+    // native bond is only possible if stake token is a native denom
+    let amount = match &cfg.stake_token {
+        AssetInfo::Native(denom) => must_pay(&info, denom)?,
+        _ => return Err(ContractError::NoFund {}),
+    };
+    let balance = Balance {
+        denom: cfg.stake_token,
+        amount,
+        sender: info.sender,
+    };
+    execute_bond(deps, env, balance, duration)
 }
 
 pub fn execute_bond(
     deps: DepsMut,
     env: Env,
-    balance: CW20Balance,
+    balance: Balance,
     duration: u128,
 ) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
+    if cfg.bonding_paused() {
+        return Err(ContractError::BondingPaused {});
+    }
     // check denom
-    if balance.denom != cfg.stake_token_address {
+    if balance.denom != cfg.stake_token {
         return Err(ContractError::InvalidCw20TokenAddress {});
     }
     // check duration
@@ -152,62 +423,24 @@ pub fn execute_bond(
         return Err(ContractError::NoFund {});
     }
     let mut state = STATE.load(deps.storage)?;
-    // look for this address and desired duration in STAKERS
-    let staker = STAKERS.may_load(deps.storage, (&balance.sender, duration))?;
-    match staker {
-        Some(mut staker) => {
-            update_staker_rewards(&mut state, env.block.time, &mut staker, cfg)?;
-            // add to existing staker
-            staker.staked_amount = staker.staked_amount.add(amount);
-            // update total weight. Its a bit tricky to update total weight so i remove the old weight and add new weight.
-            let _new_weight = Decimal256::from_ratio(duration, Uint128::one())
-                .sqrt()
-                .checked_mul(Decimal256::from_ratio(staker.staked_amount, Uint128::one()))?;
-            state.total_weight = state
-                .total_weight
-                .checked_sub(staker.position_weight)?
-                .checked_add(
-                    Decimal256::from_ratio(duration, Uint128::one())
-                        .sqrt()
-                        .checked_mul(Decimal256::from_ratio(
-                            staker.staked_amount,
-                            Uint128::one(),
-                        ))?,
-                )?;
-            staker.position_weight = Decimal256::from_ratio(duration, Uint128::one())
-                .sqrt()
-                .checked_mul(Decimal256::from_ratio(staker.staked_amount, Uint128::one()))?;
-
-            STAKERS.save(deps.storage, (&balance.sender, duration), &staker)?;
-        }
-        None => {
-            // create new staker
-            update_reward_index(&mut state, env.block.time, cfg)?;
-            let position_weight = Decimal256::from_ratio(duration, Uint128::one())
-                .sqrt()
-                .checked_mul(Decimal256::from_ratio(amount, Uint128::one()))?;
-
-            let staker = StakePosition {
-                staked_amount: amount,
-                index: state.global_index,
-                bond_time: env.block.time,
-                unbond_duration_as_days: duration,
-                pending_rewards: Uint128::zero(),
-                dec_rewards: Decimal256::zero(),
-                last_claimed: env.block.time,
-                position_weight,
-            };
-            state.total_weight = state.total_weight.add(position_weight);
-
-            STAKERS.save(deps.storage, (&balance.sender, duration), &staker)?;
-        }
-    }
-    state.total_staked = state.total_staked.add(amount);
+    // every bond creates a new position
+    update_reward_index(&mut state, env.block.time)?;
+    let position_id = create_position(
+        deps.storage,
+        &mut state,
+        &balance.sender,
+        amount,
+        duration,
+        env.block.time,
+        false,
+    )?;
+    state.total_staked = state.total_staked.checked_add(amount)?;
     STATE.save(deps.storage, &state)?;
 
     let res = Response::new()
         .add_attribute("action", "bond")
         .add_attribute("sender", balance.sender)
+        .add_attribute("position_id", position_id.to_string())
         .add_attribute("amount", amount)
         .add_attribute("duration_day", duration.to_string());
 
@@ -216,50 +449,147 @@ pub fn execute_bond(
 
 pub fn execute_update_reward_index(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
     let mut state = STATE.load(deps.storage)?;
-    let config = CONFIG.load(deps.storage)?;
 
-    update_reward_index(&mut state, env.block.time, config)?;
+    update_reward_index(&mut state, env.block.time)?;
 
     STATE.save(deps.storage, &state)?;
 
-    let res = Response::new()
-        .add_attribute("action", "update_reward_index")
-        .add_attribute("new_index", state.global_index.to_string())
-        .add_attribute(
-            "total_reward_claimed",
-            state.total_reward_claimed.to_string(),
-        );
+    let mut res = Response::new().add_attribute("action", "update_reward_index");
+    for reward_state in state.rewards.values() {
+        res = res
+            .add_attribute("reward_token", reward_state.reward_token.to_string())
+            .add_attribute("new_index", reward_state.global_index.to_string())
+            .add_attribute(
+                "total_reward_claimed",
+                reward_state.total_reward_claimed.to_string(),
+            );
+    }
     Ok(res)
 }
 
-pub fn update_reward_index(
-    state: &mut State,
-    now: Timestamp,
-    config: Config,
-) -> Result<(), ContractError> {
-    // new distribution balance = (now - last_updated) * reward_per_second
-    let seconds_since_last_updated =
-        Uint128::from(now.seconds().saturating_sub(state.last_updated.seconds()));
-    let new_dist_balance = seconds_since_last_updated.checked_mul(config.reward_per_second)?;
-
+pub fn update_reward_index(state: &mut State, now: Timestamp) -> Result<(), ContractError> {
+    let last_updated = state.last_updated;
     let divider = state.total_weight;
-    // adding index = new distribution balance / total weight
-    let adding_index = Decimal256::from_ratio(new_dist_balance, Uint256::one())
-        .checked_div(divider)
-        .unwrap_or(Decimal256::zero());
-    if !state.total_weight.is_zero() {
-        state.total_reward_claimed = state.total_reward_claimed.checked_add(new_dist_balance)?;
-        state.global_index = state.global_index.add(adding_index);
+    if state.rewards_frozen {
+        // emission stops while frozen and continues from the same point afterwards
+        let frozen_seconds = now.seconds().saturating_sub(last_updated.seconds());
+        for reward_state in state.rewards.values_mut() {
+            if let Some(end_time) = reward_state.reward_end_time {
+                if end_time > last_updated {
+                    reward_state.reward_end_time = Some(end_time.plus_seconds(frozen_seconds));
+                }
+            }
+            for schedule in reward_state.schedules.iter_mut() {
+                schedule.postpone(last_updated, now);
+            }
+        }
+        state.last_updated = now;
+        return Ok(());
+    }
+    for reward_state in state.rewards.values_mut() {
+        // rewards are distributed until reward end time
+        let distribution_end = match reward_state.reward_end_time {
+            Some(end_time) if end_time < now => end_time,
+            _ => now,
+        };
+        // new distribution balance = (min(now, end) - last_updated) * reward_per_second
+        let seconds_since_last_updated = Uint128::from(
+            distribution_end
+                .seconds()
+                .saturating_sub(last_updated.seconds()),
+        );
+        // scheduled rewards are distributed linearly between schedule start and end
+        let mut scheduled_dist_balance = Uint128::zero();
+        for schedule in reward_state.schedules.iter_mut() {
+            // without weight nothing can be distributed, the schedule runs once someone bonds
+            if divider.is_zero() {
+                schedule.postpone(last_updated, now);
+                continue;
+            }
+            let distributed = schedule.distributed_at(now);
+            scheduled_dist_balance =
+                scheduled_dist_balance.checked_add(distributed - schedule.distributed)?;
+            schedule.distributed = distributed;
+        }
+        reward_state.schedules.retain(|schedule| schedule.end > now);
+        // distribution can not exceed the funded rewards that are not distributed or scheduled yet
+        let new_dist_balance = seconds_since_last_updated
+            .checked_mul(reward_state.reward_per_second)?
+            .min(
+                reward_state
+                    .unscheduled_rewards()?
+                    .saturating_sub(scheduled_dist_balance),
+            )
+            .checked_add(scheduled_dist_balance)?;
+
+        // adding index = new distribution balance / total weight
+        let adding_index = Decimal256::from_ratio(new_dist_balance, Uint256::one())
+            .checked_div(divider)
+            .unwrap_or(Decimal256::zero());
+        if !divider.is_zero() {
+            reward_state.total_reward_claimed = reward_state
+                .total_reward_claimed
+                .checked_add(new_dist_balance)?;
+            reward_state.remaining_rewards = reward_state
+                .remaining_rewards
+                .checked_sub(new_dist_balance)?;
+            reward_state.global_index = reward_state.global_index.add(adding_index);
+        }
     }
     state.last_updated = now;
     Ok(())
 }
 
+// saves a new position and adds its weight to total weight.
+// reward index must be updated before calling this
+fn create_position(
+    storage: &mut dyn Storage,
+    state: &mut State,
+    owner: &Addr,
+    amount: Uint128,
+    duration: u128,
+    now: Timestamp,
+    auto_compound: bool,
+) -> Result<u64, ContractError> {
+    let position_id = next_position_id(storage)?;
+    let position = StakePosition {
+        owner: owner.clone(),
+        staked_amount: amount,
+        bond_time: now,
+        unbond_duration_as_days: duration,
+        last_claimed: now,
+        position_weight: calculate_position_weight(duration, amount)?,
+        rewards: new_position_rewards(state),
+        auto_compound,
+    };
+    state.total_weight = state.total_weight.checked_add(position.position_weight)?;
+    save_position(storage, position_id, &position)?;
+    Ok(position_id)
+}
+
+// new positions start from the current index of every reward stream
+pub fn new_position_rewards(state: &State) -> BTreeMap<String, PositionReward> {
+    state
+        .rewards
+        .iter()
+        .map(|(key, reward_state)| {
+            (
+                key.clone(),
+                PositionReward {
+                    index: reward_state.global_index,
+                    ..PositionReward::default()
+                },
+            )
+        })
+        .collect()
+}
+
 pub fn execute_update_staker_rewards(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     address: Option<String>,
+    position_id: Option<u64>,
 ) -> Result<Response, ContractError> {
     let mut state = STATE.load(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
@@ -269,31 +599,35 @@ pub fn execute_update_staker_rewards(
         return Err(ContractError::NoBond {});
     }
     // stakers rewards are updated for every duration and current rewards summed to return response
-    let rewards: Uint128 = STAKERS
-        .range(deps.storage, None, None, Order::Ascending)
-        .collect::<StdResult<Vec<_>>>()?
-        .into_iter()
-        .filter(|(staker, _)| staker.0 == addr)
-        .map(|(_, mut staker)| {
-            let reward =
-                update_staker_rewards(&mut state, env.block.time, &mut staker, config.clone())
-                    .unwrap_or(Uint128::zero());
-            STAKERS
-                .save(
-                    deps.storage,
-                    (&addr, staker.unbond_duration_as_days),
-                    &staker,
-                )
-                .unwrap_or_default();
-            reward
-        })
-        .sum();
+    let mut rewards = AssetList::new();
+    let mut compounded = Uint128::zero();
+    let positions = match position_id {
+        Some(position_id) => vec![(
+            position_id,
+            load_owned_position(deps.storage, position_id, &addr)?,
+        )],
+        None => load_owner_positions(deps.storage, &addr, None, usize::MAX)?,
+    };
+    for (position_id, mut staker) in positions {
+        update_staker_rewards(&mut state, env.block.time, &mut staker)?;
+        if staker.auto_compound && !config.bonding_paused() && !config.rewards_paused() {
+            compounded = compounded.checked_add(compound_position(
+                &mut state,
+                &config.stake_token,
+                &mut staker,
+            )?)?;
+        }
+        rewards.add_many(&pending_rewards(&state, &staker))?;
+        save_position(deps.storage, position_id, &staker)?;
+    }
+    rewards.purge();
 
     STATE.save(deps.storage, &state)?;
     let res = Response::new()
         .add_attribute("action", "update_stakers_rewards")
         .add_attribute("address", addr)
-        .add_attribute("rewards", rewards.to_string());
+        .add_attribute("rewards", rewards.to_string())
+        .add_attribute("compounded", compounded);
     Ok(res)
 }
 
@@ -301,87 +635,299 @@ pub fn update_staker_rewards(
     state: &mut State,
     now: Timestamp,
     stake_position: &mut StakePosition,
-    config: Config,
-) -> Result<Uint128, ContractError> {
+) -> Result<AssetList, ContractError> {
     //update reward index
-    update_reward_index(state, now, config)?;
-
-    let index_diff = state.global_index - stake_position.index;
-    // new distributed reward = index diff * position weight + dec rewards
-    let new_distributed_reward = index_diff
-        .checked_mul(stake_position.position_weight)?
-        .checked_add(stake_position.dec_rewards)?;
-    // decimals are used to store the remainder of the division
-    let decimals = get_decimals(new_distributed_reward)?;
-
-    let rewards_uint128 = (new_distributed_reward * Uint256::one())
-        .try_into()
-        .unwrap_or(Uint128::zero());
-    stake_position.dec_rewards = decimals;
-    stake_position.pending_rewards = stake_position
-        .pending_rewards
-        .checked_add(rewards_uint128)?;
-    // update stakers index
-    stake_position.index = state.global_index;
+    update_reward_index(state, now)?;
+
+    let mut pending_rewards = AssetList::new();
+    for (key, reward_state) in state.rewards.iter() {
+        // reward streams added after bonding start from zero index
+        let position_reward = stake_position.rewards.entry(key.clone()).or_default();
+        let index_diff = reward_state.global_index - position_reward.index;
+        // new distributed reward = index diff * position weight + dec rewards
+        let new_distributed_reward = index_diff
+            .checked_mul(stake_position.position_weight)?
+            .checked_add(position_reward.dec_rewards)?;
+        // decimals are used to store the remainder of the division
+        let decimals = get_decimals(new_distributed_reward)?;
+
+        let rewards_uint128 = (new_distributed_reward * Uint256::one())
+            .try_into()
+            .unwrap_or(Uint128::zero());
+        position_reward.dec_rewards = decimals;
+        position_reward.pending_rewards = position_reward
+            .pending_rewards
+            .checked_add(rewards_uint128)?;
+        // update stakers index
+        position_reward.index = reward_state.global_index;
+        pending_rewards.add(&Asset::new(
+            reward_state.reward_token.clone(),
+            position_reward.pending_rewards,
+        ))?;
+    }
     // update last claimed time. This is used to return data for the reward calculation
     stake_position.last_claimed = now;
-    Ok(stake_position.pending_rewards)
+    Ok(pending_rewards)
 }
 
 pub fn execute_receive_reward(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    position_id: Option<u64>,
 ) -> Result<Response, ContractError> {
     let mut state = STATE.load(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
+    if config.rewards_paused() {
+        return Err(ContractError::RewardsPaused {});
+    }
 
-    let rewards: Uint128 = STAKERS
-        .prefix(&info.sender)
-        .range(deps.storage, None, None, Order::Ascending)
-        .collect::<StdResult<Vec<_>>>()?
-        .into_iter()
-        .map(|(_, mut staker)| {
-            let reward =
-                update_staker_rewards(&mut state, env.block.time, &mut staker, config.clone())
-                    .unwrap();
-            // set pending rewards to zero.
-            staker.pending_rewards = Uint128::zero();
-            STAKERS
-                .save(
-                    deps.storage,
-                    (&info.sender, staker.unbond_duration_as_days),
-                    &staker,
-                )
-                .unwrap_or_default();
-            reward
-        })
-        .sum();
+    let positions = match position_id {
+        Some(position_id) => vec![(
+            position_id,
+            load_owned_position(deps.storage, position_id, &info.sender)?,
+        )],
+        None => load_owner_positions(deps.storage, &info.sender, None, usize::MAX)?,
+    };
+    let mut rewards = AssetList::new();
+    let mut compounded = Uint128::zero();
+    for (position_id, mut staker) in positions {
+        update_staker_rewards(&mut state, env.block.time, &mut staker)?;
+        // auto compounded rewards stay in the contract
+        if staker.auto_compound && !config.bonding_paused() {
+            compounded = compounded.checked_add(compound_position(
+                &mut state,
+                &config.stake_token,
+                &mut staker,
+            )?)?;
+        }
+        rewards.add_many(&pending_rewards(&state, &staker))?;
+        // set pending rewards to zero.
+        clear_pending_rewards(&mut staker);
+        // positions unbonded while rewards were paused are kept until their rewards are received
+        if staker.staked_amount.is_zero() {
+            remove_position(deps.storage, position_id)?;
+        } else {
+            save_position(deps.storage, position_id, &staker)?;
+        }
+    }
     STATE.save(deps.storage, &state)?;
-    let reward_asset = Asset::cw20(config.reward_token_address, rewards);
-    let reward_msg = reward_asset.transfer_msg(info.sender.clone())?;
+    // every reward token is sent in the same transaction
+    let messages = rewards.purge().transfer_msgs(info.sender.clone())?;
     let res = Response::new()
-        .add_message(reward_msg)
+        .add_messages(messages)
         .add_attribute("action", "receive_reward")
         .add_attribute("address", info.sender)
-        .add_attribute("rewards", rewards.to_string());
+        .add_attribute("rewards", rewards.to_string())
+        .add_attribute("compounded", compounded);
     Ok(res)
 }
 
-pub fn execute_unbond(
+pub fn execute_compound(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    position_id: u64,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    if config.bonding_paused() {
+        return Err(ContractError::BondingPaused {});
+    }
+    if config.rewards_paused() {
+        return Err(ContractError::RewardsPaused {});
+    }
+
+    let mut staker = load_owned_position(deps.storage, position_id, &info.sender)?;
+    update_staker_rewards(&mut state, env.block.time, &mut staker)?;
+    let amount = compound_position(&mut state, &config.stake_token, &mut staker)?;
+    if amount.is_zero() {
+        return Err(ContractError::NoRewardToCompound {});
+    }
+    save_position(deps.storage, position_id, &staker)?;
+    STATE.save(deps.storage, &state)?;
+
+    let res = Response::new()
+        .add_attribute("action", "compound")
+        .add_attribute("address", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("position_id", position_id.to_string());
+    Ok(res)
+}
+
+pub fn execute_set_auto_compound(
+    deps: DepsMut,
+    info: MessageInfo,
+    position_id: u64,
+    auto_compound: bool,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    if !state.rewards.contains_key(&config.stake_token.to_string()) {
+        return Err(ContractError::CompoundNotSupported {});
+    }
+    let mut staker = load_owned_position(deps.storage, position_id, &info.sender)?;
+    staker.auto_compound = auto_compound;
+    save_position(deps.storage, position_id, &staker)?;
+
+    let res = Response::new()
+        .add_attribute("action", "set_auto_compound")
+        .add_attribute("address", info.sender)
+        .add_attribute("position_id", position_id.to_string())
+        .add_attribute("auto_compound", auto_compound.to_string());
+    Ok(res)
+}
+
+pub fn execute_extend_lock(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    position_id: u64,
+    to_duration: u128,
     amount: Option<Uint128>,
-    duration_as_days: u128,
 ) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.bonding_paused() {
+        return Err(ContractError::BondingPaused {});
+    }
     let mut state = STATE.load(deps.storage)?;
+
+    let mut staker = load_owned_position(deps.storage, position_id, &info.sender)?;
+    if to_duration <= staker.unbond_duration_as_days {
+        return Err(ContractError::InvalidLockExtension {});
+    }
+    if to_duration > config.max_bond_duration {
+        return Err(ContractError::InvalidBondDuration {});
+    }
+    let amount = amount.unwrap_or(staker.staked_amount);
+    if amount.is_zero() {
+        return Err(ContractError::NoFund {});
+    }
+    if amount > staker.staked_amount {
+        return Err(ContractError::InsufficientStakedAmount {});
+    }
+    // rewards are settled with the old weight before moving the stake
+    update_staker_rewards(&mut state, env.block.time, &mut staker)?;
+    state.total_weight = state.total_weight.checked_sub(staker.position_weight)?;
+
+    let new_position_id = if amount == staker.staked_amount {
+        // whole position is moved to the new duration
+        staker.unbond_duration_as_days = to_duration;
+        staker.position_weight = calculate_position_weight(to_duration, staker.staked_amount)?;
+        state.total_weight = state.total_weight.checked_add(staker.position_weight)?;
+        save_position(deps.storage, position_id, &staker)?;
+        position_id
+    } else {
+        // extended amount is split into a new position starting from the current index
+        staker.staked_amount = staker.staked_amount.checked_sub(amount)?;
+        staker.position_weight =
+            calculate_position_weight(staker.unbond_duration_as_days, staker.staked_amount)?;
+        state.total_weight = state.total_weight.checked_add(staker.position_weight)?;
+        save_position(deps.storage, position_id, &staker)?;
+
+        create_position(
+            deps.storage,
+            &mut state,
+            &info.sender,
+            amount,
+            to_duration,
+            env.block.time,
+            staker.auto_compound,
+        )?
+    };
+    STATE.save(deps.storage, &state)?;
+
+    let res = Response::new()
+        .add_attribute("action", "extend_lock")
+        .add_attribute("address", info.sender)
+        .add_attribute("position_id", position_id.to_string())
+        .add_attribute("new_position_id", new_position_id.to_string())
+        .add_attribute("amount", amount)
+        .add_attribute("to_duration", to_duration.to_string());
+    Ok(res)
+}
+
+// move pending rewards paid in stake token to the staked amount of the position.
+// staker rewards must be updated before calling this
+fn compound_position(
+    state: &mut State,
+    stake_token: &AssetInfo,
+    stake_position: &mut StakePosition,
+) -> Result<Uint128, ContractError> {
+    let position_reward = stake_position
+        .rewards
+        .get_mut(&stake_token.to_string())
+        .ok_or(ContractError::CompoundNotSupported {})?;
+    let amount = position_reward.pending_rewards;
+    if amount.is_zero() {
+        return Ok(amount);
+    }
+    position_reward.pending_rewards = Uint128::zero();
+
+    stake_position.staked_amount = stake_position.staked_amount.checked_add(amount)?;
+    // remove the old weight and add the new weight
+    state.total_weight = state
+        .total_weight
+        .checked_sub(stake_position.position_weight)?;
+    stake_position.position_weight = calculate_position_weight(
+        stake_position.unbond_duration_as_days,
+        stake_position.staked_amount,
+    )?;
+    state.total_weight = state
+        .total_weight
+        .checked_add(stake_position.position_weight)?;
+    state.total_staked = state.total_staked.checked_add(amount)?;
+    Ok(amount)
+}
+
+// position weight = sqrt(duration) * amount
+pub fn calculate_position_weight(
+    duration: u128,
+    amount: Uint128,
+) -> Result<Decimal256, ContractError> {
+    Ok(Decimal256::from_ratio(duration, Uint128::one())
+        .sqrt()
+        .checked_mul(Decimal256::from_ratio(amount, Uint128::one()))?)
+}
+
+// pending rewards of every reward stream of the position
+fn pending_rewards(state: &State, stake_position: &StakePosition) -> AssetList {
+    stake_position
+        .rewards
+        .iter()
+        .filter_map(|(key, reward)| {
+            state.rewards.get(key).map(|reward_state| {
+                Asset::new(reward_state.reward_token.clone(), reward.pending_rewards)
+            })
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+pub fn execute_unbond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Option<Uint128>,
+    position_id: u64,
+) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
+    if config.unbonding_paused() {
+        return Err(ContractError::UnbondingPaused {});
+    }
+    let mut state = STATE.load(deps.storage)?;
 
-    let mut staker = STAKERS.load(deps.storage, (&info.sender, duration_as_days))?;
-    // rewards for desired duration is updated and pending rewards are set to zero
-    let reward = update_staker_rewards(&mut state, env.block.time, &mut staker, config.clone())?;
-    staker.pending_rewards = Uint128::zero();
+    let mut staker = load_owned_position(deps.storage, position_id, &info.sender)?;
+    let duration_as_days = staker.unbond_duration_as_days;
+    // rewards for desired position is updated and pending rewards are set to zero
+    let mut rewards = update_staker_rewards(&mut state, env.block.time, &mut staker)?;
+    // rewards are kept in the position while rewards are paused
+    let keep_rewards = config.rewards_paused();
+    if keep_rewards {
+        rewards = AssetList::new();
+    } else {
+        clear_pending_rewards(&mut staker);
+    }
 
     let unbond_amount = match amount {
         Some(amount) => {
@@ -390,18 +936,24 @@ pub fn execute_unbond(
             }
             staker.staked_amount = staker.staked_amount.checked_sub(amount)?;
             state.total_weight = state.total_weight.checked_sub(staker.position_weight)?;
-            let position_weight = Decimal256::from_ratio(duration_as_days, Uint128::one())
-                .sqrt()
-                .checked_mul(Decimal256::from_ratio(staker.staked_amount, Uint128::one()))?;
-            staker.position_weight = position_weight;
+            staker.position_weight =
+                calculate_position_weight(duration_as_days, staker.staked_amount)?;
             state.total_weight = state.total_weight.checked_add(staker.position_weight)?;
-            STAKERS.save(deps.storage, (&info.sender, duration_as_days), &staker)?;
+            save_position(deps.storage, position_id, &staker)?;
             amount
         }
         None => {
             state.total_weight = state.total_weight.checked_sub(staker.position_weight)?;
-            STAKERS.remove(deps.storage, (&info.sender, duration_as_days));
-            staker.staked_amount
+            let amount = staker.staked_amount;
+            if keep_rewards {
+                // emptied position is kept until its rewards are received
+                staker.staked_amount = Uint128::zero();
+                staker.position_weight = Decimal256::zero();
+                save_position(deps.storage, position_id, &staker)?;
+            } else {
+                remove_position(deps.storage, position_id)?;
+            }
+            amount
         }
     };
     state.total_staked = state.total_staked.checked_sub(unbond_amount)?;
@@ -422,133 +974,841 @@ pub fn execute_unbond(
         &claim,
     )?;
 
-    let mut messages: Vec<CosmosMsg> = vec![];
-    let reward_asset = Asset::cw20(config.reward_token_address, reward);
-    let reward_msg = reward_asset.transfer_msg(info.sender.clone())?;
-
-    if reward > Uint128::zero() {
-        messages.push(reward_msg);
-    }
+    let messages = rewards.purge().transfer_msgs(info.sender.clone())?;
 
     let res = Response::new()
         .add_messages(messages)
         .add_attribute("action", "unbond")
         .add_attribute("address", info.sender)
+        .add_attribute("position_id", position_id.to_string())
         .add_attribute("amount", unbond_amount)
         .add_attribute("duration", duration_as_days.to_string());
 
     Ok(res)
 }
 //update config
+#[allow(clippy::too_many_arguments)]
 pub fn execute_update_config(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     force_claim_ratio: Option<Decimal>,
+    force_claim_curve: Option<FeeCurve>,
+    fee_pool_ratio: Option<Decimal>,
+    fee_burn_ratio: Option<Decimal>,
     fee_collector: Option<String>,
-    admin: Option<String>,
+    max_bond_duration: Option<u128>,
 ) -> Result<Response, ContractError> {
-    let mut config = CONFIG.load(deps.storage)?;
-    if info.sender != config.admin {
+    let config = CONFIG.load(deps.storage)?;
+    assert_role(deps.storage, &config, Role::FeeManager, &info.sender)?;
+    // bond duration is not a fee setting
+    if max_bond_duration.is_some() && info.sender != config.admin {
         return Err(ContractError::Unauthorized {});
     }
-    if let Some(force_claim_ratio) = force_claim_ratio {
+    let update = ConfigUpdate {
+        fee_collector: maybe_addr(deps.api, fee_collector)?,
+        force_claim_ratio,
+        force_claim_curve,
+        fee_pool_ratio,
+        fee_burn_ratio,
+        max_bond_duration,
+    };
+    // invalid changes are rejected before they are queued
+    apply_config_update(&mut config.clone(), &update)?;
+    queue_change(deps, env, config, Change::UpdateConfig(update))
+}
+
+// validates the update like instantiate and returns an attribute for every changed field
+fn apply_config_update(
+    config: &mut Config,
+    update: &ConfigUpdate,
+) -> Result<Vec<Attribute>, ContractError> {
+    let mut attributes = vec![];
+    if let Some(force_claim_ratio) = update.force_claim_ratio {
+        if force_claim_ratio >= Decimal::one() {
+            return Err(ContractError::InvalidForceClaimRatio {});
+        }
         config.force_claim_ratio = force_claim_ratio;
+        attributes.push(attr("force_claim_ratio", force_claim_ratio.to_string()));
+    }
+    if let Some(force_claim_curve) = &update.force_claim_curve {
+        force_claim_curve.validate()?;
+        config.force_claim_curve = force_claim_curve.clone();
+        attributes.push(attr(
+            "force_claim_curve",
+            String::from_utf8(to_vec(force_claim_curve)?)
+                .map_err(|e| StdError::invalid_utf8(e.to_string()))?,
+        ));
     }
-    if let Some(fee_collector) = fee_collector {
-        config.fee_collector = deps.api.addr_validate(&fee_collector)?;
+    if let Some(fee_pool_ratio) = update.fee_pool_ratio {
+        if fee_pool_ratio > Decimal::one() {
+            return Err(ContractError::InvalidFeePoolRatio {});
+        }
+        config.fee_pool_ratio = fee_pool_ratio;
+        attributes.push(attr("fee_pool_ratio", fee_pool_ratio.to_string()));
     }
-    if let Some(admin) = admin {
-        config.admin = deps.api.addr_validate(&admin)?;
+    if let Some(fee_burn_ratio) = update.fee_burn_ratio {
+        config.fee_burn_ratio = fee_burn_ratio;
+        attributes.push(attr("fee_burn_ratio", fee_burn_ratio.to_string()));
     }
-    CONFIG.save(deps.storage, &config)?;
-    Ok(Response::default())
+    // pool ratio or burn ratio may be changed separately so their sum is checked after both
+    validate_fee_burn_ratio(config.fee_burn_ratio, config.fee_pool_ratio)?;
+    if let Some(fee_collector) = &update.fee_collector {
+        config.fee_collector = fee_collector.clone();
+        attributes.push(attr("fee_collector", fee_collector));
+    }
+    if let Some(max_bond_duration) = update.max_bond_duration {
+        if max_bond_duration < 1 {
+            return Err(ContractError::InvalidMaxBondDuration {});
+        }
+        config.max_bond_duration = max_bond_duration;
+        attributes.push(attr("max_bond_duration", max_bond_duration.to_string()));
+    }
+    Ok(attributes)
 }
 
-pub fn execute_claim(
+pub fn execute_set_timelock_delay(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    timelock_delay: u64,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let claims = Claims::new(CLAIMS_KEY);
-    // load mature claims where release_at < now using second key.
-    let mature_claims: Vec<Claim> =
-        claims.load_mature_claims(deps.storage, info.sender.clone(), env.block.time.seconds())?;
-    // if no mature claims return error
-    if mature_claims.is_empty() {
-        return Err(ContractError::NoMatureClaim {});
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
     }
-    // sum mature claims
-    let total_claim: Uint128 = mature_claims.into_iter().map(|c| c.amount).sum();
-
-    // remove mature claims from storage
-    claims.remove_mature_claims(deps.storage, info.sender.clone(), env.block.time.seconds())?;
-
-    let stake_asset = Asset::cw20(config.stake_token_address, total_claim);
-    let asset_message = stake_asset.transfer_msg(info.sender)?;
-
-    let res = Response::new()
-        .add_message(asset_message)
-        .add_attribute("action", "claim")
-        .add_attribute("amount", total_claim.to_string());
-    Ok(res)
+    queue_change(
+        deps,
+        env,
+        config,
+        Change::SetTimelockDelay { timelock_delay },
+    )
 }
 
-pub fn execute_force_claim(
+// applies the change now if there is no timelock delay, otherwise queues it
+fn queue_change(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
-    release_at: Timestamp,
+    config: Config,
+    change: Change,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    let mut claims =
-        Claims::new(CLAIMS_KEY).load(deps.storage, info.sender.clone(), release_at.seconds())?;
-
-    if claims.is_empty() {
-        return Err(ContractError::NoClaimForTimestamp {});
+    if config.timelock_delay == 0 {
+        return apply_change(deps.storage, env.block.time, change);
     }
+    let change_id = next_change_id(deps.storage)?;
+    let executable_at = env.block.time.plus_seconds(config.timelock_delay);
+    QUEUED_CHANGES.save(
+        deps.storage,
+        change_id,
+        &QueuedChange {
+            change,
+            executable_at,
+        },
+    )?;
 
-    if release_at.seconds() < env.block.time.seconds() {
-        return Err(ContractError::InvalidReleaseTime {});
-    }
+    let res = Response::new()
+        .add_attribute("action", "queue_change")
+        .add_attribute("change_id", change_id.to_string())
+        .add_attribute("executable_at", executable_at.to_string());
+    Ok(res)
+}
+
+// changes are validated again as the config may have changed while they were queued
+fn apply_change(
+    storage: &mut dyn Storage,
+    now: Timestamp,
+    change: Change,
+) -> Result<Response, ContractError> {
+    match change {
+        Change::UpdateConfig(update) => {
+            let mut config = CONFIG.load(storage)?;
+            let attributes = apply_config_update(&mut config, &update)?;
+            CONFIG.save(storage, &config)?;
+            Ok(Response::new()
+                .add_attribute("action", "update_config")
+                .add_attributes(attributes))
+        }
+        Change::SetRewardPerSecond {
+            reward_token,
+            reward_per_second,
+        } => {
+            let mut state = STATE.load(storage)?;
+            update_reward_index(&mut state, now)?;
+            let reward_state = state
+                .rewards
+                .get_mut(&reward_token.to_string())
+                .ok_or(ContractError::RewardStreamNotFound {})?;
+            reward_state.reward_per_second = reward_per_second;
+            STATE.save(storage, &state)?;
+            Ok(Response::default()
+                .add_attribute("action", "set_reward_per_second".to_string())
+                .add_attribute("reward_token", reward_token.to_string())
+                .add_attribute("reward_per_second", reward_per_second.to_string()))
+        }
+        Change::SetTimelockDelay { timelock_delay } => {
+            let mut config = CONFIG.load(storage)?;
+            config.timelock_delay = timelock_delay;
+            CONFIG.save(storage, &config)?;
+            Ok(Response::new()
+                .add_attribute("action", "set_timelock_delay")
+                .add_attribute("timelock_delay", timelock_delay.to_string()))
+        }
+    }
+}
+
+pub fn execute_change(deps: DepsMut, env: Env, change_id: u64) -> Result<Response, ContractError> {
+    let queued = QUEUED_CHANGES
+        .may_load(deps.storage, change_id)?
+        .ok_or(ContractError::ChangeNotFound {})?;
+    if env.block.time < queued.executable_at {
+        return Err(ContractError::ChangeNotExecutable {
+            executable_at: queued.executable_at,
+        });
+    }
+    QUEUED_CHANGES.remove(deps.storage, change_id);
+    let res = apply_change(deps.storage, env.block.time, queued.change)?;
+    Ok(res.add_attribute("change_id", change_id.to_string()))
+}
+
+pub fn execute_cancel_change(
+    deps: DepsMut,
+    info: MessageInfo,
+    change_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !QUEUED_CHANGES.has(deps.storage, change_id) {
+        return Err(ContractError::ChangeNotFound {});
+    }
+    QUEUED_CHANGES.remove(deps.storage, change_id);
+
+    let res = Response::new()
+        .add_attribute("action", "cancel_change")
+        .add_attribute("change_id", change_id.to_string());
+    Ok(res)
+}
+
+pub fn execute_propose_new_admin(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    new_admin: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    let new_admin = deps.api.addr_validate(&new_admin)?;
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::AdminTransferExpired {});
+    }
+    PENDING_ADMIN_EXPIRY.save(deps.storage, &expires)?;
+    PENDING_ADMIN.set(deps, Some(new_admin.clone()))?;
+
+    let res = Response::new()
+        .add_attribute("action", "propose_new_admin")
+        .add_attribute("new_admin", new_admin)
+        .add_attribute("expires", expires.to_string());
+    Ok(res)
+}
+
+pub fn execute_accept_admin(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let pending_admin = PENDING_ADMIN
+        .get(deps.as_ref())?
+        .ok_or(ContractError::NoPendingAdmin {})?;
+    if info.sender != pending_admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    if PENDING_ADMIN_EXPIRY
+        .load(deps.storage)?
+        .is_expired(&env.block)
+    {
+        return Err(ContractError::AdminTransferExpired {});
+    }
+    let mut config = CONFIG.load(deps.storage)?;
+    let previous_admin = config.admin;
+    config.admin = pending_admin;
+    CONFIG.save(deps.storage, &config)?;
+    PENDING_ADMIN_EXPIRY.remove(deps.storage);
+    PENDING_ADMIN.set(deps, None)?;
+
+    let res = Response::new()
+        .add_attribute("action", "accept_admin")
+        .add_attribute("previous_admin", previous_admin)
+        .add_attribute("admin", config.admin);
+    Ok(res)
+}
+
+pub fn execute_cancel_admin_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    if PENDING_ADMIN.get(deps.as_ref())?.is_none() {
+        return Err(ContractError::NoPendingAdmin {});
+    }
+    PENDING_ADMIN_EXPIRY.remove(deps.storage);
+    PENDING_ADMIN.set(deps, None)?;
+
+    Ok(Response::new().add_attribute("action", "cancel_admin_transfer"))
+}
+
+fn assert_role(
+    store: &dyn Storage,
+    config: &Config,
+    role: Role,
+    address: &Addr,
+) -> Result<(), ContractError> {
+    if !has_role(store, config, &role, address)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+pub fn execute_grant_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    let address = deps.api.addr_validate(&address)?;
+    if ROLES.has(deps.storage, (role.as_str(), &address)) {
+        return Err(ContractError::RoleAlreadyGranted {});
+    }
+    ROLES.save(deps.storage, (role.as_str(), &address), &Empty {})?;
+
+    let res = Response::new()
+        .add_attribute("action", "grant_role")
+        .add_attribute("role", role.as_str())
+        .add_attribute("address", address);
+    Ok(res)
+}
+
+pub fn execute_revoke_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    let address = deps.api.addr_validate(&address)?;
+    if !ROLES.has(deps.storage, (role.as_str(), &address)) {
+        return Err(ContractError::RoleNotGranted {});
+    }
+    ROLES.remove(deps.storage, (role.as_str(), &address));
+
+    let res = Response::new()
+        .add_attribute("action", "revoke_role")
+        .add_attribute("role", role.as_str())
+        .add_attribute("address", address);
+    Ok(res)
+}
+
+pub fn execute_set_pause(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bonding: Option<bool>,
+    rewards: Option<bool>,
+    unbonding: Option<bool>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    // pauser can only pause, unpausing is left to admin
+    let unpause = [bonding, rewards, unbonding].contains(&Some(false));
+    if info.sender != config.admin
+        && (unpause || !has_role(deps.storage, &config, &Role::Pauser, &info.sender)?)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(bonding) = bonding {
+        config.pause.bonding = bonding;
+    }
+    if let Some(rewards) = rewards {
+        config.pause.rewards = rewards;
+    }
+    if let Some(unbonding) = unbonding {
+        config.pause.unbonding = unbonding;
+    }
+    CONFIG.save(deps.storage, &config)?;
+    freeze_rewards(deps.storage, env.block.time, config.rewards_paused())?;
+
+    let res = Response::new()
+        .add_attribute("action", "set_pause")
+        .add_attribute("bonding", config.pause.bonding.to_string())
+        .add_attribute("rewards", config.pause.rewards.to_string())
+        .add_attribute("unbonding", config.pause.unbonding.to_string());
+    Ok(res)
+}
+
+pub fn execute_set_emergency(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    emergency: bool,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    config.emergency = emergency;
+    CONFIG.save(deps.storage, &config)?;
+    freeze_rewards(deps.storage, env.block.time, config.rewards_paused())?;
+
+    let res = Response::new()
+        .add_attribute("action", "set_emergency")
+        .add_attribute("emergency", emergency.to_string());
+    Ok(res)
+}
+
+// rewards are distributed until now before emission is frozen or resumed
+fn freeze_rewards(
+    storage: &mut dyn Storage,
+    now: Timestamp,
+    frozen: bool,
+) -> Result<(), ContractError> {
+    let mut state = STATE.load(storage)?;
+    update_reward_index(&mut state, now)?;
+    state.rewards_frozen = frozen;
+    STATE.save(storage, &state)?;
+    Ok(())
+}
+
+// withdraws positions and then claims of the sender oldest first, every one if limit is
+// not set. Pending rewards are forfeited
+pub fn execute_emergency_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if !config.emergency {
+        return Err(ContractError::NotEmergency {});
+    }
+    let limit = match limit {
+        Some(0) => return Err(ContractError::InvalidLimit {}),
+        Some(limit) => limit as usize,
+        None => usize::MAX,
+    };
+    let mut state = STATE.load(deps.storage)?;
+    // index is settled with the current weight before the positions are removed
+    update_reward_index(&mut state, env.block.time)?;
+
+    // one more entry than the limit tells if anything is left for another transaction
+    let mut positions =
+        load_owner_positions(deps.storage, &info.sender, None, limit.saturating_add(1))?;
+    let mut has_more = positions.len() > limit;
+    positions.truncate(limit);
+    let withdrawn_positions = positions.len();
+    let mut staked_amount = Uint128::zero();
+    for (position_id, staker) in positions {
+        staked_amount = staked_amount.checked_add(staker.staked_amount)?;
+        state.total_weight = state.total_weight.checked_sub(staker.position_weight)?;
+        remove_position(deps.storage, position_id)?;
+    }
+    state.total_staked = state.total_staked.checked_sub(staked_amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    // claims are withdrawn with the rest of the limit after every position
+    let claims = Claims::new(CLAIMS_KEY);
+    let mut withdrawn_claims = 0;
+    let mut unbonding_amount = Uint128::zero();
+    if !has_more {
+        let claim_limit = limit - withdrawn_positions;
+        let mut claim_page = claims.load_page(
+            deps.storage,
+            info.sender.clone(),
+            None,
+            None,
+            None,
+            claim_limit.saturating_add(1),
+        )?;
+        has_more = claim_page.len() > claim_limit;
+        claim_page.truncate(claim_limit);
+        withdrawn_claims = claim_page.len();
+        for (release_at, id, claim) in claim_page {
+            unbonding_amount = unbonding_amount.checked_add(claim.amount)?;
+            claims.remove(deps.storage, info.sender.clone(), release_at, id)?;
+        }
+    }
+    if withdrawn_positions == 0 && withdrawn_claims == 0 {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    let amount = staked_amount.checked_add(unbonding_amount)?;
+    let mut res = Response::new();
+    // positions emptied while rewards were paused have nothing to transfer
+    if !amount.is_zero() {
+        res = res
+            .add_message(Asset::new(config.stake_token, amount).transfer_msg(info.sender.clone())?);
+    }
+    let res = res
+        .add_attribute("action", "emergency_withdraw")
+        .add_attribute("address", info.sender)
+        .add_attribute("staked_amount", staked_amount)
+        .add_attribute("unbonding_amount", unbonding_amount)
+        .add_attribute("amount", amount)
+        .add_attribute("has_more", has_more.to_string());
+    Ok(res)
+}
+
+pub fn execute_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    release_at: Option<Timestamp>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.unbonding_paused() {
+        return Err(ContractError::UnbondingPaused {});
+    }
+    let claims = Claims::new(CLAIMS_KEY);
+    // load oldest mature claims where release_at <= now using second key.
+    let first_pending = env.block.time.seconds() + 1;
+    let (min_release, max_release) = match release_at {
+        // claims of a release time in the future are not mature
+        Some(release_at) => (
+            Some(release_at.seconds()),
+            Some(first_pending.min(release_at.seconds() + 1)),
+        ),
+        None => (None, Some(first_pending)),
+    };
+    let limit = match limit {
+        Some(0) => return Err(ContractError::InvalidLimit {}),
+        Some(limit) => limit as usize,
+        None => usize::MAX,
+    };
+    let mature_claims = claims.load_page(
+        deps.storage,
+        info.sender.clone(),
+        None,
+        min_release,
+        max_release,
+        limit,
+    )?;
+    // if no mature claims return error
+    if mature_claims.is_empty() {
+        return Err(ContractError::NoMatureClaim {});
+    }
+    let claimed_count = mature_claims.len();
+    let mut total_claim = Uint128::zero();
+    for (release_at, id, claim) in mature_claims {
+        total_claim = total_claim.checked_add(claim.amount)?;
+        // remove mature claim from storage
+        claims.remove(deps.storage, info.sender.clone(), release_at, id)?;
+    }
+    // mature claims left for following transactions, counted up to a maximum
+    // so the count stays bounded for accounts with many claims
+    let remaining_count = claims.count(
+        deps.storage,
+        info.sender.clone(),
+        None,
+        Some(first_pending),
+        MAX_REMAINING_COUNT,
+    );
+
+    let stake_asset = Asset::new(config.stake_token, total_claim);
+    let asset_message = stake_asset.transfer_msg(info.sender)?;
+
+    let res = Response::new()
+        .add_message(asset_message)
+        .add_attribute("action", "claim")
+        .add_attribute("amount", total_claim.to_string())
+        .add_attribute("claimed_count", claimed_count.to_string())
+        .add_attribute("remaining_count", remaining_count.to_string());
+    Ok(res)
+}
+
+pub fn execute_force_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    release_at: Timestamp,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.unbonding_paused() {
+        return Err(ContractError::UnbondingPaused {});
+    }
+    let claims = Claims::new(CLAIMS_KEY);
+    let release_claims =
+        claims.load_with_ids(deps.storage, info.sender.clone(), release_at.seconds())?;
+    let parts = force_claim_parts(&config, release_claims, release_at, amount, env.block.time)?;
 
-    let remaining_time = release_at.minus_seconds(env.block.time.seconds()).seconds();
     let mut total_fee: Uint128 = Uint128::zero();
     let mut total_claim_amount: Uint128 = Uint128::zero();
-    for c in claims.iter_mut() {
-        let total_unbond_duration = c.release_at.minus_seconds(c.unbond_at.seconds()).seconds();
-        let cut_ratio = config
-            .force_claim_ratio
-            .checked_mul(Decimal::from_ratio(remaining_time, total_unbond_duration))?;
-        let cut_amount = c
-            .amount
-            .multiply_ratio(cut_ratio.numerator(), cut_ratio.denominator());
-
-        let claim_amount = c.amount.checked_sub(cut_amount)?;
-        total_fee = total_fee.checked_add(cut_amount)?;
+    for part in parts {
+        let claim_amount = part.amount.checked_sub(part.fee)?;
+        total_fee = total_fee.checked_add(part.fee)?;
         total_claim_amount = total_claim_amount.checked_add(claim_amount)?;
+
+        // remainder of the claim keeps its release and unbond time
+        let (id, mut c) = (part.id, part.claim);
+        c.amount = c.amount.checked_sub(part.amount)?;
+        if c.amount.is_zero() {
+            claims.remove(deps.storage, info.sender.clone(), release_at.seconds(), id)?;
+        } else {
+            claims.update(
+                deps.storage,
+                info.sender.clone(),
+                release_at.seconds(),
+                id,
+                &c,
+            )?;
+        }
     }
 
-    //send cut_amount to fee_collector
-    let fee_asset = Asset::cw20(config.stake_token_address.clone(), total_fee);
-    let fee_message = fee_asset.transfer_msg(config.fee_collector)?;
+    let mut state = STATE.load(deps.storage)?;
+    // pool share of the fee is distributed to remaining stakers
+    let pool_amount = distribute_fee_to_stakers(
+        &mut state,
+        &config,
+        total_fee.multiply_ratio(
+            config.fee_pool_ratio.numerator(),
+            config.fee_pool_ratio.denominator(),
+        ),
+        env.block.time,
+    )?;
+    let burn_amount = total_fee.multiply_ratio(
+        config.fee_burn_ratio.numerator(),
+        config.fee_burn_ratio.denominator(),
+    );
+    let collector_amount = total_fee
+        .checked_sub(pool_amount)?
+        .checked_sub(burn_amount)?;
+    state.total_fees_burned = state.total_fees_burned.checked_add(burn_amount)?;
+    state.total_fees_collected = state.total_fees_collected.checked_add(collector_amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    let mut res = Response::new();
+    if !burn_amount.is_zero() {
+        res = res.add_message(burn_msg(&config.stake_token, burn_amount)?);
+    }
+    //send rest of the cut_amount to fee_collector
+    if !collector_amount.is_zero() {
+        let fee_asset = Asset::new(config.stake_token.clone(), collector_amount);
+        res = res.add_message(fee_asset.transfer_msg(config.fee_collector)?);
+    }
     //send claim_amount to user
-    let claim_asset = Asset::cw20(config.stake_token_address, total_claim_amount);
-    let claim_message = claim_asset.transfer_msg(info.sender.clone())?;
+    let claim_asset = Asset::new(config.stake_token, total_claim_amount);
+    let claim_message = claim_asset.transfer_msg(info.sender)?;
 
-    //remove claim from storage
-    Claims::new(CLAIMS_KEY).remove_for_release_at(
-        deps.storage,
-        info.sender,
-        release_at.seconds(),
-    )?;
-    let res = Response::new()
-        .add_message(fee_message)
+    let res = res
         .add_message(claim_message)
         .add_attribute("action", "force_claim")
         .add_attribute("amount", total_claim_amount.to_string())
-        .add_attribute("cut_amount", total_fee.to_string());
+        .add_attribute("cut_amount", total_fee.to_string())
+        .add_attribute("pool_amount", pool_amount.to_string())
+        .add_attribute("burn_amount", burn_amount.to_string())
+        .add_attribute("collector_amount", collector_amount.to_string());
+    Ok(res)
+}
+
+fn burn_msg(token: &AssetInfo, amount: Uint128) -> Result<CosmosMsg, ContractError> {
+    let msg = match token {
+        AssetInfo::Native(denom) => CosmosMsg::Bank(BankMsg::Burn {
+            amount: coins(amount.u128(), denom),
+        }),
+        AssetInfo::Cw20(contract_addr) => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+            funds: vec![],
+        }),
+        _ => return Err(ContractError::AssetError {}),
+    };
+    Ok(msg)
+}
+
+fn validate_fee_burn_ratio(
+    fee_burn_ratio: Decimal,
+    fee_pool_ratio: Decimal,
+) -> Result<(), ContractError> {
+    match fee_burn_ratio.checked_add(fee_pool_ratio) {
+        Ok(total) if total <= Decimal::one() => Ok(()),
+        _ => Err(ContractError::InvalidFeeBurnRatio {}),
+    }
+}
+
+// part of a claim withdrawn by force claim
+struct ForceClaimPart {
+    id: u16,
+    claim: Claim,
+    amount: Uint128,
+    fee: Uint128,
+}
+
+// splits the amount over the claims with the release time in saving order and
+// calculates the fee of every part. every claim is withdrawn if amount is not set
+fn force_claim_parts(
+    config: &Config,
+    release_claims: Vec<(u16, Claim)>,
+    release_at: Timestamp,
+    amount: Option<Uint128>,
+    now: Timestamp,
+) -> Result<Vec<ForceClaimPart>, ContractError> {
+    if release_claims.is_empty() {
+        return Err(ContractError::NoClaimForTimestamp {});
+    }
+
+    if release_at.seconds() < now.seconds() {
+        return Err(ContractError::InvalidReleaseTime {});
+    }
+
+    let total_amount: Uint128 = release_claims.iter().map(|(_, c)| c.amount).sum();
+    let amount = amount.unwrap_or(total_amount);
+    if amount.is_zero() || amount > total_amount {
+        return Err(ContractError::InvalidForceClaimAmount {});
+    }
+
+    let mut remaining_amount = amount;
+    let mut parts = vec![];
+    for (id, claim) in release_claims {
+        if remaining_amount.is_zero() {
+            break;
+        }
+        let withdraw_amount = claim.amount.min(remaining_amount);
+        let fee = force_claim_cut(config, &claim, withdraw_amount, now)?;
+        remaining_amount = remaining_amount.checked_sub(withdraw_amount)?;
+        parts.push(ForceClaimPart {
+            id,
+            claim,
+            amount: withdraw_amount,
+            fee,
+        });
+    }
+    Ok(parts)
+}
+
+// adds amount of stake token to the global index of the stake token reward stream.
+// the stream is created if it does not exist. returns the distributed amount which is
+// zero if there are no stakers or the stream can not be created
+fn distribute_fee_to_stakers(
+    state: &mut State,
+    config: &Config,
+    amount: Uint128,
+    now: Timestamp,
+) -> Result<Uint128, ContractError> {
+    if amount.is_zero() {
+        return Ok(amount);
+    }
+    if state.total_weight.is_zero() {
+        return Ok(Uint128::zero());
+    }
+    let key = config.stake_token.to_string();
+    if !state.rewards.contains_key(&key) && state.rewards.len() >= MAX_REWARD_STREAMS {
+        return Ok(Uint128::zero());
+    }
+    update_reward_index(state, now)?;
+    let total_weight = state.total_weight;
+    let reward_state = state
+        .rewards
+        .entry(key)
+        .or_insert_with(|| RewardState::new(config.stake_token.clone()));
+    // fee is distributed at once so it is counted as funded and claimed
+    reward_state.global_index = reward_state
+        .global_index
+        .checked_add(Decimal256::from_ratio(amount, Uint256::one()) / total_weight)?;
+    reward_state.total_reward_supply = reward_state.total_reward_supply.checked_add(amount)?;
+    reward_state.total_reward_claimed = reward_state.total_reward_claimed.checked_add(amount)?;
+    Ok(amount)
+}
+
+// fee for withdrawing amount of the claim before its release time
+// fee = amount * fee ratio of the force claim curve
+fn force_claim_cut(
+    config: &Config,
+    claim: &Claim,
+    amount: Uint128,
+    now: Timestamp,
+) -> StdResult<Uint128> {
+    let cut_ratio = force_claim_ratio_at(config, claim.unbond_at, claim.release_at, now)?;
+    Ok(amount.multiply_ratio(cut_ratio.numerator(), cut_ratio.denominator()))
+}
+
+fn force_claim_ratio_at(
+    config: &Config,
+    unbond_at: Timestamp,
+    release_at: Timestamp,
+    now: Timestamp,
+) -> StdResult<Decimal> {
+    let elapsed = now.seconds().saturating_sub(unbond_at.seconds());
+    let total_unbond_duration = release_at.seconds().saturating_sub(unbond_at.seconds());
+    config
+        .force_claim_curve
+        .fee_ratio(config.force_claim_ratio, elapsed, total_unbond_duration)
+}
+
+pub fn execute_rebond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    release_at: Timestamp,
+    duration_as_days: u128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.bonding_paused() {
+        return Err(ContractError::BondingPaused {});
+    }
+    if duration_as_days < 1 || duration_as_days > config.max_bond_duration {
+        return Err(ContractError::InvalidBondDuration {});
+    }
+    let claims = Claims::new(CLAIMS_KEY);
+    let release_claims = claims.load(deps.storage, info.sender.clone(), release_at.seconds())?;
+    if release_claims.is_empty() {
+        return Err(ContractError::NoClaimForTimestamp {});
+    }
+    // a shorter rebond would skip the rest of the unbonding duration and the force claim fee
+    let rebond_duration = days_to_seconds(duration_as_days);
+    if release_claims
+        .iter()
+        .any(|c| rebond_duration < c.release_at.seconds() - c.unbond_at.seconds())
+    {
+        return Err(ContractError::InvalidRebondDuration {});
+    }
+    let amount: Uint128 = release_claims.into_iter().map(|c| c.amount).sum();
+    claims.remove_for_release_at(deps.storage, info.sender.clone(), release_at.seconds())?;
+
+    // claimed tokens are still in the contract so they are staked again without transfer
+    let mut state = STATE.load(deps.storage)?;
+    update_reward_index(&mut state, env.block.time)?;
+    let position_id = create_position(
+        deps.storage,
+        &mut state,
+        &info.sender,
+        amount,
+        duration_as_days,
+        env.block.time,
+        false,
+    )?;
+    state.total_staked = state.total_staked.checked_add(amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    let res = Response::new()
+        .add_attribute("action", "rebond")
+        .add_attribute("address", info.sender)
+        .add_attribute("position_id", position_id.to_string())
+        .add_attribute("amount", amount)
+        .add_attribute("release_at", release_at.to_string())
+        .add_attribute("duration", duration_as_days.to_string());
     Ok(res)
 }
 
@@ -557,36 +1817,143 @@ pub fn execute_set_reward_per_second(
     env: Env,
     info: MessageInfo,
     reward_per_second: Uint128,
+    reward_token: Option<AssetInfoUnchecked>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    assert_role(deps.storage, &config, Role::RewardManager, &info.sender)?;
+    if reward_per_second <= Uint128::zero() {
+        return Err(ContractError::InvalidRewardPerSecond {});
+    };
+    let reward_token = match reward_token {
+        Some(reward_token) => reward_token.check(deps.api, None)?,
+        None => config.reward_token.clone(),
+    };
+    if !state.rewards.contains_key(&reward_token.to_string()) {
+        return Err(ContractError::RewardStreamNotFound {});
+    }
+    let change = Change::SetRewardPerSecond {
+        reward_token,
+        reward_per_second,
+    };
+    queue_change(deps, env, config, change)
+}
+
+pub fn execute_add_reward_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    reward_token: AssetInfoUnchecked,
 ) -> Result<Response, ContractError> {
     let mut state = STATE.load(deps.storage)?;
-    let mut config = CONFIG.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
 
     if info.sender != config.admin {
         return Err(ContractError::Unauthorized {});
     };
-    if reward_per_second <= Uint128::zero() {
-        return Err(ContractError::InvalidRewardPerSecond {});
-    };
-    update_reward_index(&mut state, env.block.time, config.clone())?;
+    let reward_token = reward_token.check(deps.api, None)?;
+    let key = reward_token.to_string();
+    if state.rewards.contains_key(&key) {
+        return Err(ContractError::RewardStreamExists {});
+    }
+    if state.rewards.len() >= MAX_REWARD_STREAMS {
+        return Err(ContractError::TooManyRewardStreams {});
+    }
+    // distribute existing streams before adding the new one
+    update_reward_index(&mut state, env.block.time)?;
+    state
+        .rewards
+        .insert(key, RewardState::new(reward_token.clone()));
     STATE.save(deps.storage, &state)?;
-    config.reward_per_second = reward_per_second;
-    CONFIG.save(deps.storage, &config)?;
     Ok(Response::default()
-        .add_attribute("action", "set_reward_per_second".to_string())
-        .add_attribute("reward_per_second", reward_per_second.to_string()))
+        .add_attribute("action", "add_reward_stream")
+        .add_attribute("reward_token", reward_token.to_string()))
+}
+
+// set pending rewards of every reward stream to zero after they are sent
+fn clear_pending_rewards(stake_position: &mut StakePosition) {
+    stake_position
+        .rewards
+        .values_mut()
+        .for_each(|reward| reward.pending_rewards = Uint128::zero());
 }
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::State {} => to_binary(&query_state(deps, env, msg)?),
         QueryMsg::Config {} => to_binary(&query_config(deps, env, msg)?),
-        QueryMsg::StakerForDuration { address, duration } => {
-            to_binary(&query_staker_for_duration(env, deps, address, duration)?)
+        QueryMsg::PendingAdmin {} => to_binary(&query_pending_admin(deps)?),
+        QueryMsg::PendingChanges { start_after, limit } => {
+            to_binary(&query_pending_changes(deps, start_after, limit)?)
+        }
+        QueryMsg::RoleMembers {
+            role,
+            start_after,
+            limit,
+        } => to_binary(&query_role_members(deps, role, start_after, limit)?),
+        QueryMsg::Position { position_id } => to_binary(&query_position(deps, env, position_id)?),
+        QueryMsg::StakerForAllDuration {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&query_staker_for_all_duration(
+            deps,
+            env,
+            address,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::AllPositions { start_after, limit } => {
+            to_binary(&query_all_positions(deps, env, start_after, limit)?)
         }
-        QueryMsg::StakerForAllDuration { address } => {
-            to_binary(&query_staker_for_all_duration(deps, env, address)?)
+        QueryMsg::AllStakers { start_after, limit } => {
+            to_binary(&query_all_stakers(deps, env, start_after, limit)?)
+        }
+        QueryMsg::PendingRewards {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&query_pending_rewards(
+            deps,
+            env,
+            address,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::ListClaims {
+            address,
+            start_after,
+            limit,
+            status,
+        } => to_binary(&query_list_claims(
+            env,
+            deps,
+            address,
+            start_after,
+            limit,
+            status,
+        )?),
+        QueryMsg::SimulateForceClaim {
+            address,
+            release_at,
+            amount,
+        } => to_binary(&query_simulate_force_claim(
+            deps, env, address, release_at, amount,
+        )?),
+        QueryMsg::ForceClaimFee {
+            amount,
+            unbond_at,
+            release_at,
+        } => to_binary(&query_force_claim_fee(
+            deps, env, amount, unbond_at, release_at,
+        )?),
+        QueryMsg::RewardRunway { reward_token } => {
+            to_binary(&query_reward_runway(deps, env, reward_token)?)
+        }
+        QueryMsg::RewardSchedules { reward_token } => {
+            to_binary(&query_reward_schedules(deps, env, reward_token)?)
         }
-        QueryMsg::ListClaims { address } => to_binary(&query_list_claims(env, deps, address)?),
     }
 }
 
@@ -594,11 +1961,170 @@ pub fn query_state(deps: Deps, _env: Env, _msg: QueryMsg) -> StdResult<StateResp
     let state = STATE.load(deps.storage)?;
 
     Ok(StateResponse {
-        global_index: state.global_index,
         total_staked: state.total_staked,
         total_weight: state.total_weight,
-        total_reward_claimed: state.total_reward_claimed,
         last_updated: state.last_updated,
+        rewards: state
+            .rewards
+            .into_values()
+            .map(|reward_state| RewardStateResponse {
+                reward_token: reward_state.reward_token,
+                global_index: reward_state.global_index,
+                reward_per_second: reward_state.reward_per_second,
+                reward_end_time: reward_state.reward_end_time,
+                total_reward_claimed: reward_state.total_reward_claimed,
+                total_reward_supply: reward_state.total_reward_supply,
+                remaining_rewards: reward_state.remaining_rewards,
+            })
+            .collect(),
+        total_fees_burned: state.total_fees_burned,
+        total_fees_collected: state.total_fees_collected,
+    })
+}
+
+pub fn query_reward_runway(
+    deps: Deps,
+    env: Env,
+    reward_token: Option<AssetInfoUnchecked>,
+) -> StdResult<RewardRunwayResponse> {
+    let mut state = STATE.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    let reward_token = match reward_token {
+        Some(reward_token) => reward_token
+            .check(deps.api, None)
+            .map_err(|e| StdError::generic_err(e.to_string()))?,
+        None => config.reward_token,
+    };
+    // distribute rewards up to now without saving to get current remaining rewards
+    update_reward_index(&mut state, env.block.time)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    let reward_state = state
+        .rewards
+        .remove(&reward_token.to_string())
+        .ok_or_else(|| StdError::not_found("RewardState"))?;
+
+    // seconds left until remaining rewards are fully distributed with current reward per second
+    // or the reward end time stops the distribution
+    let runway_seconds = match reward_state.reward_end_time {
+        Some(end_time) if end_time <= env.block.time => None,
+        end_time => reward_state
+            .unscheduled_rewards()
+            .map_err(|e| StdError::generic_err(e.to_string()))?
+            .checked_div(reward_state.reward_per_second)
+            .ok()
+            .map(|runway| {
+                let runway = runway.u128() as u64;
+                match end_time {
+                    Some(end_time) => runway.min(end_time.seconds() - env.block.time.seconds()),
+                    None => runway,
+                }
+            }),
+    };
+    Ok(RewardRunwayResponse {
+        reward_token: reward_state.reward_token,
+        total_reward_supply: reward_state.total_reward_supply,
+        total_reward_claimed: reward_state.total_reward_claimed,
+        remaining_rewards: reward_state.remaining_rewards,
+        reward_per_second: reward_state.reward_per_second,
+        runway_seconds,
+        depleted_at: runway_seconds.map(|runway| env.block.time.plus_seconds(runway)),
+    })
+}
+
+pub fn query_reward_schedules(
+    deps: Deps,
+    env: Env,
+    reward_token: Option<AssetInfoUnchecked>,
+) -> StdResult<RewardSchedulesResponse> {
+    let mut state = STATE.load(deps.storage)?;
+    let reward_token = reward_token
+        .map(|reward_token| reward_token.check(deps.api, None))
+        .transpose()
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    // distribute rewards up to now without saving to drop finished schedules
+    update_reward_index(&mut state, env.block.time)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    let schedules = state
+        .rewards
+        .into_values()
+        .filter(|reward_state| match &reward_token {
+            Some(reward_token) => reward_state.reward_token == *reward_token,
+            None => true,
+        })
+        .flat_map(|reward_state| {
+            let reward_token = reward_state.reward_token;
+            reward_state
+                .schedules
+                .into_iter()
+                .map(move |schedule| RewardScheduleResponse {
+                    reward_token: reward_token.clone(),
+                    start: schedule.start,
+                    end: schedule.end,
+                    amount: schedule.amount,
+                    distributed: schedule.distributed,
+                    active: schedule.start <= env.block.time,
+                })
+        })
+        .collect();
+    Ok(RewardSchedulesResponse { schedules })
+}
+
+pub fn query_simulate_force_claim(
+    deps: Deps,
+    env: Env,
+    address: String,
+    release_at: Timestamp,
+    amount: Option<Uint128>,
+) -> StdResult<SimulateForceClaimResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let addr = deps.api.addr_validate(&address)?;
+    let release_claims =
+        Claims::new(CLAIMS_KEY).load_with_ids(deps.storage, addr, release_at.seconds())?;
+    let parts = force_claim_parts(&config, release_claims, release_at, amount, env.block.time)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    let mut res = SimulateForceClaimResponse {
+        claims: vec![],
+        total_amount: Uint128::zero(),
+        total_fee: Uint128::zero(),
+        total_net_amount: Uint128::zero(),
+    };
+    for part in parts {
+        let net_amount = part.amount.checked_sub(part.fee)?;
+        res.total_amount = res.total_amount.checked_add(part.amount)?;
+        res.total_fee = res.total_fee.checked_add(part.fee)?;
+        res.total_net_amount = res.total_net_amount.checked_add(net_amount)?;
+        res.claims.push(ForceClaimSimulationResponse {
+            release_at: part.claim.release_at,
+            unbond_at: part.claim.unbond_at,
+            amount: part.amount,
+            fee: part.fee,
+            net_amount,
+        });
+    }
+    Ok(res)
+}
+
+pub fn query_force_claim_fee(
+    deps: Deps,
+    env: Env,
+    amount: Uint128,
+    unbond_at: Timestamp,
+    release_at: Timestamp,
+) -> StdResult<ForceClaimFeeResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    if release_at <= unbond_at {
+        return Err(StdError::generic_err(
+            "Release time must be after unbond time",
+        ));
+    }
+    let fee_ratio = force_claim_ratio_at(&config, unbond_at, release_at, env.block.time)?;
+    let fee = amount.multiply_ratio(fee_ratio.numerator(), fee_ratio.denominator());
+    Ok(ForceClaimFeeResponse {
+        fee_ratio,
+        fee,
+        net_amount: amount.checked_sub(fee)?,
     })
 }
 
@@ -607,77 +2133,264 @@ pub fn query_config(deps: Deps, _env: Env, _msg: QueryMsg) -> StdResult<ConfigRe
     let config = CONFIG.load(deps.storage)?;
 
     Ok(ConfigResponse {
-        reward_token_address: config.reward_token_address.to_string(),
-        stake_token_address: config.stake_token_address.to_string(),
+        reward_token: config.reward_token,
+        stake_token: config.stake_token,
         admin: config.admin.to_string(),
         fee_collector: config.fee_collector.to_string(),
         force_claim_ratio: config.force_claim_ratio.to_string(),
-        reward_per_second: config.reward_per_second,
+        force_claim_curve: config.force_claim_curve,
+        fee_pool_ratio: config.fee_pool_ratio,
+        fee_burn_ratio: config.fee_burn_ratio,
+        max_bond_duration: config.max_bond_duration,
+        pause: config.pause,
+        emergency: config.emergency,
+        timelock_delay: config.timelock_delay,
+    })
+}
+
+pub fn query_pending_admin(deps: Deps) -> StdResult<PendingAdminResponse> {
+    let pending_admin = PENDING_ADMIN.query_admin(deps)?.admin;
+    let expires = PENDING_ADMIN_EXPIRY.may_load(deps.storage)?;
+    Ok(PendingAdminResponse {
+        pending_admin,
+        expires,
     })
 }
 
-pub fn query_list_claims(_env: Env, deps: Deps, address: String) -> StdResult<ListClaimsResponse> {
+pub fn query_pending_changes(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PendingChangesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let changes = QUEUED_CHANGES
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            item.map(|(change_id, queued)| PendingChangeResponse {
+                change_id,
+                change: queued.change,
+                executable_at: queued.executable_at,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(PendingChangesResponse { changes })
+}
+
+pub fn query_role_members(
+    deps: Deps,
+    role: Role,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<RoleMembersResponse> {
+    let start_after = maybe_addr(deps.api, start_after)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let members = ROLES
+        .prefix(role.as_str())
+        .keys(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(String::from))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(RoleMembersResponse { members })
+}
+
+pub fn query_list_claims(
+    env: Env,
+    deps: Deps,
+    address: String,
+    start_after: Option<(Timestamp, u16)>,
+    limit: Option<u32>,
+    status: Option<ClaimStatus>,
+) -> StdResult<ListClaimsResponse> {
     let addr = deps.api.addr_validate(&address)?;
-    let claim = Claims::new(CLAIMS_KEY).load_all(deps.storage, addr)?;
-    let claims: Vec<ClaimResponse> = claim
+    let claims = Claims::new(CLAIMS_KEY);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // claims released until now are mature
+    let first_pending = env.block.time.seconds() + 1;
+    let (min_release, max_release) = match status {
+        Some(ClaimStatus::Mature) => (None, Some(first_pending)),
+        Some(ClaimStatus::Pending) => (Some(first_pending), None),
+        None => (None, None),
+    };
+    let page: Vec<ClaimResponse> = claims
+        .load_page(
+            deps.storage,
+            addr.clone(),
+            start_after.map(|(release_at, id)| (release_at.seconds(), id)),
+            min_release,
+            max_release,
+            limit,
+        )?
         .into_iter()
-        .map(|claim| ClaimResponse {
+        .map(|(_, id, claim)| ClaimResponse {
+            id,
             amount: claim.amount,
             release_at: claim.release_at,
             unbond_at: claim.unbond_at,
         })
         .collect();
-    Ok(ListClaimsResponse { claims })
+
+    // totals are kept in storage, so the summary does not read every claim
+    let totals = CLAIM_TOTALS
+        .may_load(deps.storage, &addr)?
+        .unwrap_or_default();
+    let summary = ClaimsSummary {
+        total_unbonding: totals.amount,
+        claim_count: totals.count,
+        next_release_at: claims
+            .load_page(deps.storage, addr, None, Some(first_pending), None, 1)?
+            .first()
+            .map(|(_, _, claim)| claim.release_at),
+    };
+    Ok(ListClaimsResponse {
+        claims: page,
+        summary,
+    })
 }
 
-pub fn query_staker_for_duration(
-    _env: Env,
-    deps: Deps,
-    address: String,
-    duration: u128,
-) -> StdResult<StakerResponse> {
-    let addr = deps.api.addr_validate(address.as_str())?;
-    let staker = STAKERS.load(deps.storage, (&addr, duration))?;
+pub fn query_position(deps: Deps, _env: Env, position_id: u64) -> StdResult<StakerResponse> {
+    let state = STATE.load(deps.storage)?;
+    let staker = stakers().load(deps.storage, position_id)?;
 
-    Ok(StakerResponse {
-        staked_amount: staker.staked_amount,
-        index: staker.index,
-        bond_time: staker.bond_time,
-        unbond_duration_as_days: staker.unbond_duration_as_days,
-        pending_rewards: staker.pending_rewards,
-        dec_rewards: staker.dec_rewards,
-        last_claimed: staker.last_claimed,
-        position_weight: staker.position_weight,
-    })
+    Ok(staker_response(&state, position_id, staker))
 }
 //query all holders list
 pub fn query_staker_for_all_duration(
     deps: Deps,
     _env: Env,
     address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
 ) -> StdResult<StakerForAllDurationResponse> {
     let addr = deps.api.addr_validate(&address)?;
-    //return all stakers of address
-    let positions: Vec<StakerResponse> = STAKERS
-        .prefix(&addr)
-        .range(deps.storage, None, None, Order::Ascending)
+    let state = STATE.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    //return stakers of address
+    let positions: Vec<StakerResponse> =
+        load_owner_positions(deps.storage, &addr, start_after, limit)?
+            .into_iter()
+            .map(|(position_id, staker)| staker_response(&state, position_id, staker))
+            .collect();
+
+    Ok(StakerForAllDurationResponse { positions })
+}
+
+pub fn query_all_positions(
+    deps: Deps,
+    _env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<AllPositionsResponse> {
+    let state = STATE.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let positions = stakers()
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(position_id, staker)| staker_response(&state, position_id, staker)))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(AllPositionsResponse { positions })
+}
+
+pub fn query_all_stakers(
+    deps: Deps,
+    _env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllStakersResponse> {
+    let start_after = maybe_addr(deps.api, start_after)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let stakers = OWNER_SUMMARIES
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
         .map(|item| {
-            let (_key, value) = item.unwrap();
-
-            StakerResponse {
-                staked_amount: value.staked_amount,
-                index: value.index,
-                bond_time: value.bond_time,
-                unbond_duration_as_days: value.unbond_duration_as_days,
-                pending_rewards: value.pending_rewards,
-                dec_rewards: value.dec_rewards,
-                last_claimed: value.last_claimed,
-                position_weight: value.position_weight,
-            }
+            item.map(|(address, summary)| StakerSummaryResponse {
+                address: address.to_string(),
+                positions: summary.positions,
+                staked_amount: summary.staked_amount,
+            })
         })
-        .collect();
+        .collect::<StdResult<_>>()?;
+    Ok(AllStakersResponse { stakers })
+}
 
-    Ok(StakerForAllDurationResponse { positions })
+pub fn query_pending_rewards(
+    deps: Deps,
+    env: Env,
+    address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PendingRewardsResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // rewards are updated on the loaded state and positions without saving
+    let mut state = STATE.load(deps.storage)?;
+    let mut positions = vec![];
+    let mut total_rewards = AssetList::new();
+    for (position_id, mut staker) in load_owner_positions(deps.storage, &addr, start_after, limit)?
+    {
+        let mut rewards = update_staker_rewards(&mut state, env.block.time, &mut staker)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+        total_rewards
+            .add_many(&rewards)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+        positions.push(PositionPendingRewardsResponse {
+            position_id,
+            unbond_duration_as_days: staker.unbond_duration_as_days,
+            rewards: rewards.purge().to_vec(),
+        });
+    }
+    Ok(PendingRewardsResponse {
+        positions,
+        total_rewards: total_rewards.purge().to_vec(),
+    })
+}
+
+fn staker_response(state: &State, position_id: u64, staker: StakePosition) -> StakerResponse {
+    StakerResponse {
+        position_id,
+        owner: staker.owner.to_string(),
+        staked_amount: staker.staked_amount,
+        bond_time: staker.bond_time,
+        unbond_duration_as_days: staker.unbond_duration_as_days,
+        last_claimed: staker.last_claimed,
+        position_weight: staker.position_weight,
+        auto_compound: staker.auto_compound,
+        rewards: staker
+            .rewards
+            .into_iter()
+            .filter_map(|(key, reward)| {
+                state
+                    .rewards
+                    .get(&key)
+                    .map(|reward_state| PositionRewardResponse {
+                        reward_token: reward_state.reward_token.clone(),
+                        index: reward.index,
+                        pending_rewards: reward.pending_rewards,
+                        dec_rewards: reward.dec_rewards,
+                    })
+            })
+            .collect(),
+    }
 }
 
 #[cfg(not(feature = "library"))]
@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cosmwasm_std::{DivideByZeroError, OverflowError, StdError, Timestamp, Uint128};
 use cw_asset::AssetError;
 use cw_utils::PaymentError;
 
@@ -12,6 +12,9 @@ pub enum ContractError {
     #[error("{0}")]
     Overflow(#[from] OverflowError),
 
+    #[error("{0}")]
+    DivideByZero(#[from] DivideByZeroError),
+
     #[error("{0}")]
     Payment(#[from] PaymentError),
 
@@ -42,6 +45,9 @@ pub enum ContractError {
     #[error("No claim for sent timestamp")]
     NoClaimForTimestamp {},
 
+    #[error("Force claim amount must be greater than 0 and not exceed the claimed amount")]
+    InvalidForceClaimAmount {},
+
     #[error("No bond")]
     NoBond {},
 
@@ -54,6 +60,15 @@ pub enum ContractError {
     #[error("No Bond for duration sent")]
     NoBondForThisDuration {},
 
+    #[error("Position not found")]
+    PositionNotFound {},
+
+    #[error("Lock can only be extended to a longer duration")]
+    InvalidLockExtension {},
+
+    #[error("Rebond duration can not be shorter than the unbonding duration of the claim")]
+    InvalidRebondDuration {},
+
     #[error("Reward per second must be greater than 0")]
     InvalidRewardPerSecond {},
 
@@ -63,6 +78,75 @@ pub enum ContractError {
     #[error("Force claim ratio must be between 0 and 1")]
     InvalidForceClaimRatio {},
 
+    #[error("Invalid force claim fee curve")]
+    InvalidFeeCurve {},
+
+    #[error("Fee pool ratio must be between 0 and 1")]
+    InvalidFeePoolRatio {},
+
+    #[error("Sum of fee burn ratio and fee pool ratio must be between 0 and 1")]
+    InvalidFeeBurnRatio {},
+
+    #[error("No reward stream for this token")]
+    RewardStreamNotFound {},
+
+    #[error("Reward stream already exists for this token")]
+    RewardStreamExists {},
+
+    #[error("Max number of reward streams reached")]
+    TooManyRewardStreams {},
+
+    #[error("Reward schedule must start in the future and end after start")]
+    InvalidRewardSchedule {},
+
+    #[error("Max number of reward schedules reached")]
+    TooManyRewardSchedules {},
+
+    #[error("Stake token is not a reward token of this pool")]
+    CompoundNotSupported {},
+
+    #[error("No rewards to compound")]
+    NoRewardToCompound {},
+
+    #[error("Queued change not found")]
+    ChangeNotFound {},
+
+    #[error("Queued change can not be executed before {executable_at}")]
+    ChangeNotExecutable { executable_at: Timestamp },
+
+    #[error("Role already granted")]
+    RoleAlreadyGranted {},
+
+    #[error("Role not granted")]
+    RoleNotGranted {},
+
+    #[error("No pending admin transfer")]
+    NoPendingAdmin {},
+
+    #[error("Admin transfer expired")]
+    AdminTransferExpired {},
+
+    #[error("Bonding is paused")]
+    BondingPaused {},
+
+    #[error("Rewards are paused")]
+    RewardsPaused {},
+
+    #[error("Unbonding is paused")]
+    UnbondingPaused {},
+
+    #[error("Contract is not in emergency mode")]
+    NotEmergency {},
+
+    #[error("Nothing to withdraw")]
+    NothingToWithdraw {},
+
+    #[error("Limit must be greater than zero")]
+    InvalidLimit {},
+
+    #[error("Reward per second can not be lowered by funding while the timelock is set")]
+    RewardRateLowered {},
+
     #[error("Asset error")]
     AssetError {},
 }
@@ -1,46 +1,161 @@
 use cosmwasm_schema::cw_serde;
 use cw20::Cw20ReceiveMsg;
+use cw_asset::{Asset, AssetInfo, AssetInfoUnchecked};
+use cw_utils::Expiration;
 
 use cosmwasm_std::{Decimal, Decimal256, Timestamp, Uint128};
 
+use crate::state::{Change, FeeCurve, PauseFlags, Role};
+
 #[cw_serde]
 pub struct InstantiateMsg {
-    pub stake_token_address: String,
-    pub reward_token_address: String,
+    pub stake_token: AssetInfoUnchecked,
+    pub reward_token: AssetInfoUnchecked,
     pub admin: Option<String>,
     pub force_claim_ratio: Decimal,
+    // defaults to linear fee curve
+    pub force_claim_curve: Option<FeeCurve>,
+    // share of force claim fees distributed to stakers. defaults to zero
+    pub fee_pool_ratio: Option<Decimal>,
+    // share of force claim fees burned. defaults to zero
+    pub fee_burn_ratio: Option<Decimal>,
     pub fee_collector: String,
     pub max_bond_duration: u128,
+    // delay of config and reward rate changes in seconds. defaults to zero
+    pub timelock_delay: Option<u64>,
 }
 
 #[cw_serde]
 
 pub enum ExecuteMsg {
     Receive(Cw20ReceiveMsg),
+    // Bond native stake token sent with the message
+    Bond {
+        duration_day: u128,
+    },
+    // Fund native reward token sent with the message
+    RewardUpdate {
+        reward_end_date: Timestamp,
+    },
+    // Schedule native reward token sent with the message to be distributed between start and end
+    ScheduleReward {
+        start: Timestamp,
+        end: Timestamp,
+    },
+    // Register a new reward stream. Admin only
+    AddRewardStream {
+        reward_token: AssetInfoUnchecked,
+    },
     UpdateRewardIndex {},
+    // Reward token defaults to the reward token set at instantiation.
+    // Queued if timelock delay is set
     SetRewardPerSecond {
         reward_per_second: Uint128,
+        reward_token: Option<AssetInfoUnchecked>,
+    },
+    // Propose a new admin that has to accept before expiry. Admin only
+    ProposeNewAdmin {
+        new_admin: String,
+        expires: Option<Expiration>,
+    },
+    // Accept the admin role. Pending admin only
+    AcceptAdmin {},
+    // Cancel the pending admin transfer. Admin only
+    CancelAdminTransfer {},
+    // Queue a change of the timelock delay. Admin only
+    SetTimelockDelay {
+        timelock_delay: u64,
+    },
+    // Execute a queued change after its delay. Anyone can execute
+    ExecuteChange {
+        change_id: u64,
     },
+    // Admin only
+    CancelChange {
+        change_id: u64,
+    },
+    // Admin only
+    GrantRole {
+        role: Role,
+        address: String,
+    },
+    // Admin only
+    RevokeRole {
+        role: Role,
+        address: String,
+    },
+    // Flags that are not set are kept. Admin, or pauser if every set flag is true
+    SetPause {
+        bonding: Option<bool>,
+        rewards: Option<bool>,
+        unbonding: Option<bool>,
+    },
+    // Admin only
+    SetEmergency {
+        emergency: bool,
+    },
+    // Withdraws staked and unbonding tokens of the sender without rewards or fee. Emergency only.
+    // Positions and then claims are withdrawn oldest first, every one if limit is not set
+    EmergencyWithdraw {
+        limit: Option<u32>,
+    },
+    // Withdraws every claim with release time if amount is not set
     ForceClaim {
         release_at: Timestamp,
+        amount: Option<Uint128>,
     },
+    // Updates rewards of every position of the address, or only the position if position_id is set
     UpdateStakerRewards {
         address: Option<String>,
+        position_id: Option<u64>,
     },
     UnbondStake {
         amount: Option<Uint128>,
+        position_id: u64,
+    },
+
+    // Claims mature claims oldest first, every mature claim if limit is not set.
+    // Only mature claims with release time if release_at is set
+    ClaimUnbonded {
+        release_at: Option<Timestamp>,
+        limit: Option<u32>,
+    },
+
+    // Cancel unbonding claims with release time and bond them again as a new position
+    Rebond {
+        release_at: Timestamp,
         duration_as_days: u128,
     },
 
-    ClaimUnbonded {},
+    // Receive rewards of every position of the sender if position id is not set
+    ReceiveReward {
+        position_id: Option<u64>,
+    },
 
-    ReceiveReward {},
+    // Add rewards paid in stake token to the position. Only if stake token is also a reward token
+    Compound {
+        position_id: u64,
+    },
+    SetAutoCompound {
+        position_id: u64,
+        auto_compound: bool,
+    },
+    // Move the whole position or amount of it to a longer duration
+    ExtendLock {
+        position_id: u64,
+        to_duration: u128,
+        amount: Option<Uint128>,
+    },
 
-    //Update config
+    //Update config. Queued if timelock delay is set
     UpdateConfig {
-        admin: Option<String>,
         fee_collector: Option<String>,
         force_claim_ratio: Option<Decimal>,
+        force_claim_curve: Option<FeeCurve>,
+        fee_pool_ratio: Option<Decimal>,
+        fee_burn_ratio: Option<Decimal>,
+        // existing positions keep their duration. Admin only
+        max_bond_duration: Option<u128>,
     },
 }
 
@@ -48,63 +163,239 @@ pub enum ExecuteMsg {
 
 pub enum ReceiveMsg {
     Bond { duration_day: u128 },
+    RewardUpdate { reward_end_date: Timestamp },
+    ScheduleReward { start: Timestamp, end: Timestamp },
 }
 
 #[cw_serde]
 pub enum QueryMsg {
     State {},
     Config {},
-    StakerForDuration { address: String, duration: u128 },
-    StakerForAllDuration { address: String },
+    PendingAdmin {},
+    // Changes waiting for the timelock delay ordered by change id
+    PendingChanges {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    // Addresses with the role ordered by address. Admin is not listed
+    RoleMembers {
+        role: Role,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Position {
+        position_id: u64,
+    },
+    // Positions of the address ordered by position id
+    StakerForAllDuration {
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    // Every position ordered by position id
+    AllPositions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    // Every address with a position ordered by address
+    AllStakers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    // Rewards of positions of the address ordered by position id as if they were updated now
+    PendingRewards {
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 
-    ListClaims { address: String },
+    // Claims of the address in release order. start_after is (release_at, id) of the last claim
+    ListClaims {
+        address: String,
+        start_after: Option<(Timestamp, u16)>,
+        limit: Option<u32>,
+        status: Option<ClaimStatus>,
+    },
+    // Result of ForceClaim of the address at current time
+    SimulateForceClaim {
+        address: String,
+        release_at: Timestamp,
+        amount: Option<Uint128>,
+    },
+    // Force claim fee of the amount for an unbonding started at unbond_at, at current time
+    ForceClaimFee {
+        amount: Uint128,
+        unbond_at: Timestamp,
+        release_at: Timestamp,
+    },
+    RewardRunway {
+        reward_token: Option<AssetInfoUnchecked>,
+    },
+    // Lists active and upcoming schedules of every reward stream if reward token is not set
+    RewardSchedules {
+        reward_token: Option<AssetInfoUnchecked>,
+    },
 }
 
 #[cw_serde]
 pub struct StateResponse {
-    pub global_index: Decimal256,
     pub total_staked: Uint128,
     pub total_weight: Decimal256,
-    pub total_reward_claimed: Uint128,
     pub last_updated: Timestamp,
+    pub rewards: Vec<RewardStateResponse>,
+    pub total_fees_burned: Uint128,
+    pub total_fees_collected: Uint128,
+}
+
+#[cw_serde]
+pub struct RewardStateResponse {
+    pub reward_token: AssetInfo,
+    pub global_index: Decimal256,
+    pub reward_per_second: Uint128,
+    pub reward_end_time: Option<Timestamp>,
+    pub total_reward_claimed: Uint128,
+    pub total_reward_supply: Uint128,
+    pub remaining_rewards: Uint128,
 }
 
 #[cw_serde]
 pub struct ConfigResponse {
-    pub stake_token_address: String,
-    pub reward_token_address: String,
+    pub stake_token: AssetInfo,
+    pub reward_token: AssetInfo,
     pub admin: String,
     pub fee_collector: String,
     pub force_claim_ratio: String,
+    pub force_claim_curve: FeeCurve,
+    pub fee_pool_ratio: Decimal,
+    pub fee_burn_ratio: Decimal,
+    pub max_bond_duration: u128,
+    pub pause: PauseFlags,
+    pub emergency: bool,
+    pub timelock_delay: u64,
+}
+
+#[cw_serde]
+pub struct ForceClaimSimulationResponse {
+    pub release_at: Timestamp,
+    pub unbond_at: Timestamp,
+    // withdrawn amount of the claim before fee
+    pub amount: Uint128,
+    pub fee: Uint128,
+    pub net_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulateForceClaimResponse {
+    pub claims: Vec<ForceClaimSimulationResponse>,
+    pub total_amount: Uint128,
+    pub total_fee: Uint128,
+    pub total_net_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct ForceClaimFeeResponse {
+    pub fee_ratio: Decimal,
+    pub fee: Uint128,
+    pub net_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct RewardRunwayResponse {
+    pub reward_token: AssetInfo,
+    pub total_reward_supply: Uint128,
+    pub total_reward_claimed: Uint128,
+    pub remaining_rewards: Uint128,
     pub reward_per_second: Uint128,
+    // None if rewards are not being distributed. Capped by the reward end time
+    pub runway_seconds: Option<u64>,
+    pub depleted_at: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct RewardScheduleResponse {
+    pub reward_token: AssetInfo,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub amount: Uint128,
+    pub distributed: Uint128,
+    pub active: bool,
+}
+
+#[cw_serde]
+pub struct RewardSchedulesResponse {
+    pub schedules: Vec<RewardScheduleResponse>,
 }
 
 #[cw_serde]
 pub struct AccruedRewardsResponse {
     pub rewards: Uint128,
 }
+#[cw_serde]
+pub enum ClaimStatus {
+    // release time has passed, claimable with ClaimUnbonded
+    Mature,
+    // still unbonding
+    Pending,
+}
+
 #[cw_serde]
 pub struct ClaimResponse {
+    pub id: u16,
     pub amount: Uint128,
     pub release_at: Timestamp,
     pub unbond_at: Timestamp,
 }
 
+// Every claim of the address regardless of pagination and status filter
+#[cw_serde]
+pub struct ClaimsSummary {
+    // amount of mature and pending claims
+    pub total_unbonding: Uint128,
+    pub claim_count: u64,
+    // earliest release time of pending claims
+    pub next_release_at: Option<Timestamp>,
+}
+
 #[cw_serde]
 pub struct ListClaimsResponse {
     pub claims: Vec<ClaimResponse>,
+    pub summary: ClaimsSummary,
 }
 
 #[cw_serde]
 pub struct StakerResponse {
+    pub position_id: u64,
+    pub owner: String,
     pub staked_amount: Uint128,
-    pub index: Decimal256,
     pub bond_time: Timestamp,
     pub unbond_duration_as_days: u128,
-    pub pending_rewards: Uint128,
-    pub dec_rewards: Decimal256,
     pub last_claimed: Timestamp,
     pub position_weight: Decimal256,
+    pub rewards: Vec<PositionRewardResponse>,
+    pub auto_compound: bool,
+}
+
+#[cw_serde]
+pub struct PositionRewardResponse {
+    pub reward_token: AssetInfo,
+    pub index: Decimal256,
+    pub pending_rewards: Uint128,
+    pub dec_rewards: Decimal256,
+}
+
+#[cw_serde]
+pub struct PositionPendingRewardsResponse {
+    pub position_id: u64,
+    pub unbond_duration_as_days: u128,
+    pub rewards: Vec<Asset>,
+}
+
+#[cw_serde]
+pub struct PendingRewardsResponse {
+    pub positions: Vec<PositionPendingRewardsResponse>,
+    // sum of rewards of the returned positions
+    pub total_rewards: Vec<Asset>,
 }
 
 #[cw_serde]
@@ -112,5 +403,45 @@ pub struct StakerForAllDurationResponse {
     pub positions: Vec<StakerResponse>,
 }
 
+#[cw_serde]
+pub struct PendingAdminResponse {
+    pub pending_admin: Option<String>,
+    pub expires: Option<Expiration>,
+}
+
+#[cw_serde]
+pub struct PendingChangeResponse {
+    pub change_id: u64,
+    pub change: Change,
+    pub executable_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct PendingChangesResponse {
+    pub changes: Vec<PendingChangeResponse>,
+}
+
+#[cw_serde]
+pub struct RoleMembersResponse {
+    pub members: Vec<String>,
+}
+
+#[cw_serde]
+pub struct AllPositionsResponse {
+    pub positions: Vec<StakerResponse>,
+}
+
+#[cw_serde]
+pub struct StakerSummaryResponse {
+    pub address: String,
+    pub positions: u32,
+    pub staked_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct AllStakersResponse {
+    pub stakers: Vec<StakerSummaryResponse>,
+}
+
 #[cw_serde]
 pub struct MigrateMsg {}
@@ -1,17 +1,103 @@
-use cosmwasm_std::{Addr, Decimal, Decimal256, Order, StdResult, Storage, Timestamp, Uint128};
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{
+    Addr, Decimal, Decimal256, Empty, Order, StdResult, Storage, Timestamp, Uint128,
+};
 
 use cosmwasm_schema::cw_serde;
-use cw_storage_plus::{Bound, Item, Map};
+use cw_asset::AssetInfo;
+use cw_controllers::Admin;
+use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::Expiration;
 
 use crate::ContractError;
 
 #[cw_serde]
 pub struct State {
-    pub global_index: Decimal256,
     pub total_staked: Uint128,
     pub total_weight: Decimal256,
-    pub total_reward_claimed: Uint128,
     pub last_updated: Timestamp,
+    // reward streams keyed by reward token e.g. "cw20:addr" or "native:denom"
+    pub rewards: BTreeMap<String, RewardState>,
+    // cumulative force claim fees burned and sent to fee collector
+    pub total_fees_burned: Uint128,
+    pub total_fees_collected: Uint128,
+    // set with the rewards pause or emergency mode. Reward indexes do not grow while frozen
+    pub rewards_frozen: bool,
+}
+
+pub const MAX_REWARD_STREAMS: usize = 10;
+pub const MAX_REWARD_SCHEDULES: usize = 10;
+
+#[cw_serde]
+pub struct RewardState {
+    pub reward_token: AssetInfo,
+    pub global_index: Decimal256,
+    pub reward_per_second: Uint128,
+    // rewards are not distributed after this time. None means open ended
+    pub reward_end_time: Option<Timestamp>,
+    pub total_reward_claimed: Uint128,
+    // sum of all reward tokens funded through RewardUpdate
+    pub total_reward_supply: Uint128,
+    // funded rewards that are not distributed yet. distribution is capped by this amount
+    pub remaining_rewards: Uint128,
+    // active and upcoming reward schedules. finished schedules are removed
+    pub schedules: Vec<RewardSchedule>,
+}
+
+impl RewardState {
+    pub fn new(reward_token: AssetInfo) -> Self {
+        RewardState {
+            reward_token,
+            global_index: Decimal256::zero(),
+            reward_per_second: Uint128::zero(),
+            reward_end_time: None,
+            total_reward_claimed: Uint128::zero(),
+            total_reward_supply: Uint128::zero(),
+            remaining_rewards: Uint128::zero(),
+            schedules: vec![],
+        }
+    }
+
+    // remaining rewards that are not reserved for reward schedules
+    pub fn unscheduled_rewards(&self) -> StdResult<Uint128> {
+        let scheduled = self
+            .schedules
+            .iter()
+            .map(|schedule| schedule.amount.checked_sub(schedule.distributed))
+            .sum::<Result<Uint128, _>>()?;
+        Ok(self.remaining_rewards.saturating_sub(scheduled))
+    }
+}
+
+#[cw_serde]
+pub struct RewardSchedule {
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub amount: Uint128,
+    pub distributed: Uint128,
+}
+
+impl RewardSchedule {
+    // amount of the schedule that should be distributed until given time
+    pub fn distributed_at(&self, time: Timestamp) -> Uint128 {
+        let elapsed = time
+            .seconds()
+            .clamp(self.start.seconds(), self.end.seconds())
+            - self.start.seconds();
+        self.amount
+            .multiply_ratio(elapsed, self.end.seconds() - self.start.seconds())
+    }
+
+    // moves the schedule forward by the part of from..to it was running,
+    // so nothing is distributed for that period
+    pub fn postpone(&mut self, from: Timestamp, to: Timestamp) {
+        let shift = to
+            .seconds()
+            .saturating_sub(from.seconds().max(self.start.seconds()));
+        self.start = self.start.plus_seconds(shift);
+        self.end = self.end.plus_seconds(shift);
+    }
 }
 
 pub const STATE: Item<State> = Item::new("state");
@@ -48,6 +134,7 @@ impl<'a> Claims<'a> {
             .map(|(id, _)| id)
             .unwrap_or(0);
 
+        update_claim_totals(store, &address, None, Some(claim))?;
         self.0
             .save(store, (address, release_at, last_id + 1), claim)
     }
@@ -65,6 +152,46 @@ impl<'a> Claims<'a> {
             .collect()
     }
 
+    // claims for the release time with their ids in saving order
+    pub fn load_with_ids(
+        &self,
+        store: &dyn Storage,
+        address: Addr,
+        release_at: u64,
+    ) -> StdResult<Vec<(u16, Claim)>> {
+        self.0
+            .prefix((address, release_at))
+            .range(store, None, None, Order::Ascending)
+            .collect()
+    }
+
+    pub fn update(
+        &self,
+        store: &mut dyn Storage,
+        address: Addr,
+        release_at: u64,
+        id: u16,
+        claim: &Claim,
+    ) -> StdResult<()> {
+        let old = self.0.load(store, (address.clone(), release_at, id))?;
+        update_claim_totals(store, &address, Some(&old), Some(claim))?;
+        self.0.save(store, (address, release_at, id), claim)
+    }
+
+    pub fn remove(
+        &self,
+        store: &mut dyn Storage,
+        address: Addr,
+        release_at: u64,
+        id: u16,
+    ) -> StdResult<()> {
+        if let Some(old) = self.0.may_load(store, (address.clone(), release_at, id))? {
+            update_claim_totals(store, &address, Some(&old), None)?;
+            self.0.remove(store, (address, release_at, id));
+        }
+        Ok(())
+    }
+
     pub fn load_all(&self, store: &dyn Storage, address: Addr) -> StdResult<Vec<Claim>> {
         self.0
             .sub_prefix(address)
@@ -73,95 +200,454 @@ impl<'a> Claims<'a> {
             .collect()
     }
 
-    pub fn load_mature_claims(
+    // claims with ids after start_after released in [min_release, max_release) in release order
+    pub fn load_page(
         &self,
         store: &dyn Storage,
         address: Addr,
-        now: u64,
-    ) -> StdResult<Vec<Claim>> {
+        start_after: Option<(u64, u16)>,
+        min_release: Option<u64>,
+        max_release: Option<u64>,
+        limit: usize,
+    ) -> StdResult<Vec<(u64, u16, Claim)>> {
+        // claim ids start from 1, so (release, 0) is before every claim of the release time
+        let min = match (start_after, min_release) {
+            (Some(start_after), Some(min_release)) if start_after < (min_release, 0) => {
+                Some(Bound::inclusive((min_release, 0)))
+            }
+            (Some(start_after), _) => Some(Bound::exclusive(start_after)),
+            (None, Some(min_release)) => Some(Bound::inclusive((min_release, 0))),
+            (None, None) => None,
+        };
+        let max = max_release.map(|max_release| Bound::exclusive((max_release, 0)));
         self.0
             .sub_prefix(address)
-            .range(
-                store,
-                None,
-                Some(Bound::exclusive((now + 1, 0))),
-                Order::Ascending,
-            )
-            .map(|x| x.map(|(_, v)| v))
-            .collect::<StdResult<Vec<_>>>()
+            .range(store, min, max, Order::Ascending)
+            .take(limit)
+            .map(|x| x.map(|((release_at, id), v)| (release_at, id, v)))
+            .collect()
     }
 
-    pub fn remove_mature_claims(
+    // number of claims released in [min_release, max_release), counted up to limit
+    pub fn count(
         &self,
-        store: &mut dyn Storage,
+        store: &dyn Storage,
         address: Addr,
-        now: u64,
-    ) -> Result<(), ContractError> {
+        min_release: Option<u64>,
+        max_release: Option<u64>,
+        limit: usize,
+    ) -> usize {
         self.0
-            .sub_prefix(address.clone())
-            .range(
+            .sub_prefix(address)
+            .keys_raw(
                 store,
-                None,
-                Some(Bound::exclusive((now + 1, 0))),
+                min_release.map(|min_release| Bound::inclusive((min_release, 0))),
+                max_release.map(|max_release| Bound::exclusive((max_release, 0))),
                 Order::Ascending,
             )
-            .map(|x| x.map(|(k, _v)| k))
-            .collect::<StdResult<Vec<_>>>()?
-            .into_iter()
-            .for_each(|k| self.0.remove(store, (address.clone(), k.0, k.1)));
-        Ok(())
+            .take(limit)
+            .count()
     }
+
     pub fn remove_for_release_at(
         &self,
         store: &mut dyn Storage,
         address: Addr,
         release_at: u64,
     ) -> Result<(), ContractError> {
-        self.0
-            .sub_prefix(address.clone())
-            .range(
-                store,
-                Some(Bound::inclusive((release_at, 0))),
-                Some(Bound::exclusive((release_at + 1, 0))),
-                Order::Ascending,
-            )
-            .map(|x| x.map(|(k, _v)| k))
-            .collect::<StdResult<Vec<_>>>()?
-            .into_iter()
-            .for_each(|k| self.0.remove(store, (address.clone(), k.0, k.1)));
+        for (id, _) in self.load_with_ids(store, address.clone(), release_at)? {
+            self.remove(store, address.clone(), release_at, id)?;
+        }
         Ok(())
     }
 }
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ClaimTotals {
+    pub amount: Uint128,
+    pub count: u64,
+}
+
+// unbonding amount and claim count of every address with claims
+pub const CLAIM_TOTALS: Map<&Addr, ClaimTotals> = Map::new("claim_totals");
+
+// replaces the old claim of the address with the new one in its totals
+fn update_claim_totals(
+    store: &mut dyn Storage,
+    address: &Addr,
+    old: Option<&Claim>,
+    new: Option<&Claim>,
+) -> StdResult<()> {
+    let mut totals = CLAIM_TOTALS.may_load(store, address)?.unwrap_or_default();
+    if let Some(old) = old {
+        totals.amount = totals.amount.checked_sub(old.amount)?;
+        totals.count -= 1;
+    }
+    if let Some(new) = new {
+        totals.amount = totals.amount.checked_add(new.amount)?;
+        totals.count += 1;
+    }
+    if totals.count == 0 {
+        CLAIM_TOTALS.remove(store, address);
+    } else {
+        CLAIM_TOTALS.save(store, address, &totals)?;
+    }
+    Ok(())
+}
 #[cw_serde]
 pub struct Config {
     pub admin: Addr,
-    pub stake_token_address: Addr,
-    pub reward_token_address: Addr,
+    pub stake_token: AssetInfo,
+    pub reward_token: AssetInfo,
     pub force_claim_ratio: Decimal,
+    // how the force claim fee changes during the unbonding duration
+    pub force_claim_curve: FeeCurve,
+    // share of force claim fees distributed to stakers instead of fee collector
+    pub fee_pool_ratio: Decimal,
+    // share of force claim fees burned instead of sent to fee collector
+    pub fee_burn_ratio: Decimal,
     pub fee_collector: Addr,
     pub max_bond_duration: u128,
-    pub reward_per_second: Uint128,
+    pub pause: PauseFlags,
+    // every action is paused and users can withdraw their principal with EmergencyWithdraw
+    pub emergency: bool,
+    // seconds config and reward rate changes wait before they can be executed
+    pub timelock_delay: u64,
 }
 
-pub struct CW20Balance {
-    pub denom: Addr,
+// admin proposed by the current admin until it is accepted or cancelled
+pub const PENDING_ADMIN: Admin = Admin::new("pending_admin");
+pub const PENDING_ADMIN_EXPIRY: Item<Expiration> = Item::new("pending_admin_expiry");
+
+impl Config {
+    pub fn bonding_paused(&self) -> bool {
+        self.pause.bonding || self.emergency
+    }
+
+    pub fn rewards_paused(&self) -> bool {
+        self.pause.rewards || self.emergency
+    }
+
+    pub fn unbonding_paused(&self) -> bool {
+        self.pause.unbonding || self.emergency
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseFlags {
+    // bond, rebond, extend lock and compound
+    pub bonding: bool,
+    // receive reward, compound and reward emission. Unbonded rewards are kept in the position
+    pub rewards: bool,
+    // unbond, claim and force claim
+    pub unbonding: bool,
+}
+
+pub const MAX_FEE_TIERS: usize = 10;
+
+#[cw_serde]
+pub enum FeeCurve {
+    // force_claim_ratio * remaining / total unbonding duration
+    Linear {},
+    // force_claim_ratio until release time
+    Flat {},
+    // force_claim_ratio halved every half_life seconds after unbonding
+    ExponentialDecay { half_life: u64 },
+    // ratio of the last tier started after unbonding. force_claim_ratio is not used
+    Tiered { tiers: Vec<FeeTier> },
+}
+
+#[cw_serde]
+pub struct FeeTier {
+    // seconds after unbonding this tier starts
+    pub start_after: u64,
+    pub ratio: Decimal,
+}
+
+impl Default for FeeCurve {
+    fn default() -> Self {
+        FeeCurve::Linear {}
+    }
+}
+
+impl FeeCurve {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        match self {
+            FeeCurve::Linear {} | FeeCurve::Flat {} => Ok(()),
+            FeeCurve::ExponentialDecay { half_life } => {
+                if *half_life == 0 {
+                    return Err(ContractError::InvalidFeeCurve {});
+                }
+                Ok(())
+            }
+            FeeCurve::Tiered { tiers } => {
+                // tiers must start at unbonding and be sorted by start
+                if tiers.is_empty()
+                    || tiers.len() > MAX_FEE_TIERS
+                    || tiers[0].start_after != 0
+                    || tiers
+                        .windows(2)
+                        .any(|w| w[0].start_after >= w[1].start_after)
+                    || tiers.iter().any(|tier| tier.ratio >= Decimal::one())
+                {
+                    return Err(ContractError::InvalidFeeCurve {});
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // fee ratio after elapsed seconds of total unbonding duration
+    pub fn fee_ratio(
+        &self,
+        force_claim_ratio: Decimal,
+        elapsed: u64,
+        total: u64,
+    ) -> StdResult<Decimal> {
+        if elapsed >= total {
+            return Ok(Decimal::zero());
+        }
+        let ratio = match self {
+            FeeCurve::Linear {} => {
+                force_claim_ratio.checked_mul(Decimal::from_ratio(total - elapsed, total))?
+            }
+            FeeCurve::Flat {} => force_claim_ratio,
+            FeeCurve::ExponentialDecay { half_life } => {
+                // halved for every full half life, linear between halvings
+                let halvings = elapsed / half_life;
+                if halvings >= 64 {
+                    return Ok(Decimal::zero());
+                }
+                let partial = Decimal::from_ratio(elapsed % half_life, 2 * half_life);
+                force_claim_ratio
+                    .checked_mul(Decimal::from_ratio(1u128, 1u128 << halvings))?
+                    .checked_mul(Decimal::one() - partial)?
+            }
+            FeeCurve::Tiered { tiers } => tiers
+                .iter()
+                .take_while(|tier| tier.start_after <= elapsed)
+                .last()
+                .map(|tier| tier.ratio)
+                .unwrap_or_default(),
+        };
+        Ok(ratio)
+    }
+}
+
+pub struct Balance {
+    pub denom: AssetInfo,
     pub amount: Uint128,
     pub sender: Addr,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+#[cw_serde]
+pub enum Role {
+    // funds rewards and sets reward rates
+    RewardManager,
+    // updates force claim fee settings and fee collector
+    FeeManager,
+    // pauses but can not unpause
+    Pauser,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::RewardManager => "reward_manager",
+            Role::FeeManager => "fee_manager",
+            Role::Pauser => "pauser",
+        }
+    }
+}
+
+// validated UpdateConfig fields. Fields that are not set are kept
+#[cw_serde]
+#[derive(Default)]
+pub struct ConfigUpdate {
+    pub fee_collector: Option<Addr>,
+    pub force_claim_ratio: Option<Decimal>,
+    pub force_claim_curve: Option<FeeCurve>,
+    pub fee_pool_ratio: Option<Decimal>,
+    pub fee_burn_ratio: Option<Decimal>,
+    pub max_bond_duration: Option<u128>,
+}
+
+#[cw_serde]
+pub enum Change {
+    UpdateConfig(ConfigUpdate),
+    SetRewardPerSecond {
+        reward_token: AssetInfo,
+        reward_per_second: Uint128,
+    },
+    SetTimelockDelay {
+        timelock_delay: u64,
+    },
+}
+
+#[cw_serde]
+pub struct QueuedChange {
+    pub change: Change,
+    pub executable_at: Timestamp,
+}
+
+// last queued change id
+pub const CHANGE_COUNT: Item<u64> = Item::new("change_count");
+// changes waiting for the timelock delay by change id
+pub const QUEUED_CHANGES: Map<u64, QueuedChange> = Map::new("queued_changes");
+
+pub fn next_change_id(store: &mut dyn Storage) -> StdResult<u64> {
+    let id = CHANGE_COUNT.may_load(store)?.unwrap_or_default() + 1;
+    CHANGE_COUNT.save(store, &id)?;
+    Ok(id)
+}
+
+// (role, address) -> Empty for every granted role
+pub const ROLES: Map<(&str, &Addr), Empty> = Map::new("roles");
+
+// admin has every role
+pub fn has_role(
+    store: &dyn Storage,
+    config: &Config,
+    role: &Role,
+    address: &Addr,
+) -> StdResult<bool> {
+    Ok(*address == config.admin || ROLES.has(store, (role.as_str(), address)))
+}
+
 #[cw_serde]
 pub struct StakePosition {
+    pub owner: Addr,
     pub staked_amount: Uint128,
-    pub index: Decimal256,
     pub bond_time: Timestamp,
     pub unbond_duration_as_days: u128,
-    pub pending_rewards: Uint128,
-    pub dec_rewards: Decimal256,
     pub last_claimed: Timestamp,
     pub position_weight: Decimal256,
+    // rewards of the position keyed same as State.rewards
+    pub rewards: BTreeMap<String, PositionReward>,
+    // rewards in stake token are added to the position instead of being paid out
+    pub auto_compound: bool,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct PositionReward {
+    pub index: Decimal256,
+    pub pending_rewards: Uint128,
+    pub dec_rewards: Decimal256,
+}
+
+// last position id. every bond creates a new position with the next id
+pub const POSITION_COUNT: Item<u64> = Item::new("position_count");
+
+pub struct StakerIndexes<'a> {
+    pub owner: MultiIndex<'a, Addr, StakePosition, u64>,
+}
+
+impl<'a> IndexList<StakePosition> for StakerIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<StakePosition>> + '_> {
+        let v: Vec<&dyn Index<StakePosition>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
 }
 
-// REWARDS (holder_addr, cw20_addr) -> Holder
-pub const STAKERS: Map<(&Addr, u128), StakePosition> = Map::new("stakers");
+// STAKERS position_id -> StakePosition, indexed by owner
+pub fn stakers<'a>() -> IndexedMap<'a, u64, StakePosition, StakerIndexes<'a>> {
+    let indexes = StakerIndexes {
+        owner: MultiIndex::new(
+            |_pk, position| position.owner.clone(),
+            "positions",
+            "positions__owner",
+        ),
+    };
+    IndexedMap::new("positions", indexes)
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct OwnerSummary {
+    pub positions: u32,
+    pub staked_amount: Uint128,
+}
+
+// position count and staked amount of every address with a position
+pub const OWNER_SUMMARIES: Map<&Addr, OwnerSummary> = Map::new("owner_summaries");
+
+// saves the position and keeps the summary of the owner in sync
+pub fn save_position(
+    store: &mut dyn Storage,
+    position_id: u64,
+    position: &StakePosition,
+) -> StdResult<()> {
+    let mut summary = OWNER_SUMMARIES
+        .may_load(store, &position.owner)?
+        .unwrap_or_default();
+    match stakers().may_load(store, position_id)? {
+        Some(old) => {
+            summary.staked_amount = summary.staked_amount.checked_sub(old.staked_amount)?;
+        }
+        None => summary.positions += 1,
+    }
+    summary.staked_amount = summary.staked_amount.checked_add(position.staked_amount)?;
+    OWNER_SUMMARIES.save(store, &position.owner, &summary)?;
+    stakers().save(store, position_id, position)
+}
+
+// removes the position and its part of the owner summary
+pub fn remove_position(store: &mut dyn Storage, position_id: u64) -> StdResult<()> {
+    let position = stakers().load(store, position_id)?;
+    let mut summary = OWNER_SUMMARIES.load(store, &position.owner)?;
+    summary.positions -= 1;
+    summary.staked_amount = summary.staked_amount.checked_sub(position.staked_amount)?;
+    if summary.positions == 0 {
+        OWNER_SUMMARIES.remove(store, &position.owner);
+    } else {
+        OWNER_SUMMARIES.save(store, &position.owner, &summary)?;
+    }
+    stakers().remove(store, position_id)
+}
+
+pub fn next_position_id(store: &mut dyn Storage) -> StdResult<u64> {
+    let id = POSITION_COUNT.may_load(store)?.unwrap_or_default() + 1;
+    POSITION_COUNT.save(store, &id)?;
+    Ok(id)
+}
+
+// positions of the owner after start_after ordered by position id
+pub fn load_owner_positions(
+    store: &dyn Storage,
+    owner: &Addr,
+    start_after: Option<u64>,
+    limit: usize,
+) -> StdResult<Vec<(u64, StakePosition)>> {
+    stakers()
+        .idx
+        .owner
+        .prefix(owner.clone())
+        .range(
+            store,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect()
+}
+
+// loads the position and checks that it belongs to the owner
+pub fn load_owned_position(
+    store: &dyn Storage,
+    position_id: u64,
+    owner: &Addr,
+) -> Result<StakePosition, ContractError> {
+    let position = stakers()
+        .may_load(store, position_id)?
+        .ok_or(ContractError::PositionNotFound {})?;
+    if position.owner != *owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(position)
+}
@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use std::str::FromStr;
 
@@ -6,32 +7,58 @@ mod tests {
         mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
     };
     use cosmwasm_std::{
-        from_binary, to_binary, Addr, Coin, CosmosMsg, Decimal, Decimal256, MessageInfo, Response,
-        StdError, Timestamp, Uint128, WasmMsg,
+        attr, coins, from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Decimal256,
+        DepsMut, MessageInfo, Response, StdError, Timestamp, Uint128, WasmMsg,
     };
     use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+    use cw_asset::{Asset, AssetInfo, AssetInfoUnchecked};
+    use cw_utils::{Expiration, PaymentError};
 
     use crate::contract::{
-        execute, instantiate, query, query_staker_for_all_duration, query_staker_for_duration,
-        query_state,
+        execute, instantiate, query, query_position, query_staker_for_all_duration, query_state,
     };
     use crate::msg::{
-        ClaimResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, ListClaimsResponse, QueryMsg,
-        ReceiveMsg, StakerForAllDurationResponse, StakerResponse, StateResponse,
+        AllPositionsResponse, AllStakersResponse, ClaimResponse, ClaimStatus, ClaimsSummary,
+        ConfigResponse, ExecuteMsg, ForceClaimFeeResponse, ForceClaimSimulationResponse,
+        InstantiateMsg, ListClaimsResponse, PendingAdminResponse, PendingChangesResponse,
+        PendingRewardsResponse, PositionPendingRewardsResponse, PositionRewardResponse, QueryMsg,
+        ReceiveMsg, RewardRunwayResponse, RewardScheduleResponse, RewardSchedulesResponse,
+        RoleMembersResponse, SimulateForceClaimResponse, StakerForAllDurationResponse,
+        StakerResponse, StakerSummaryResponse, StateResponse,
     };
+    use crate::state::{FeeCurve, FeeTier, Role};
     use crate::ContractError;
 
     fn default_init() -> InstantiateMsg {
         InstantiateMsg {
-            stake_token_address: "stake_token_address".to_string(),
-            reward_token_address: "reward_token_address".to_string(),
+            stake_token: AssetInfoUnchecked::cw20("stake_token_address"),
+            reward_token: AssetInfoUnchecked::cw20("reward_token_address"),
             admin: None,
             force_claim_ratio: Decimal::from_str("0.1").unwrap(),
+            force_claim_curve: None,
+            fee_pool_ratio: None,
+            fee_burn_ratio: None,
             fee_collector: "fee_collector".to_string(),
             max_bond_duration: 100,
+            timelock_delay: None,
         }
     }
 
+    // funds 100_000_000 rewards to be distributed in 100_000 seconds
+    fn fund_rewards(deps: DepsMut) {
+        let env = mock_env();
+        let info = mock_info("reward_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(100_000_000),
+            msg: to_binary(&ReceiveMsg::RewardUpdate {
+                reward_end_date: env.block.time.plus_seconds(100_000),
+            })
+            .unwrap(),
+        });
+        execute(deps, env, info, msg).unwrap();
+    }
+
     #[test]
     fn proper_init() {
         let mut deps = mock_dependencies();
@@ -50,8 +77,8 @@ mod tests {
             Response::default()
                 .add_attribute("method", "instantiate")
                 .add_attribute("admin", "creator")
-                .add_attribute("stake_token_address", "stake_token_address")
-                .add_attribute("reward_token_address", "reward_token_address")
+                .add_attribute("stake_token", "cw20:stake_token_address")
+                .add_attribute("reward_token", "cw20:reward_token_address")
                 .add_attribute("force_claim_ratio", "0.1")
                 .add_attribute("fee_collector", "fee_collector")
         );
@@ -59,12 +86,16 @@ mod tests {
         // instantiate with admin
         let mut deps = mock_dependencies();
         let init_msg = InstantiateMsg {
-            stake_token_address: "stake_token_address".to_string(),
-            reward_token_address: "reward_token_address".to_string(),
+            stake_token: AssetInfoUnchecked::cw20("stake_token_address"),
+            reward_token: AssetInfoUnchecked::cw20("reward_token_address"),
             admin: Some("admin".to_string()),
             force_claim_ratio: Decimal::from_str("0.1").unwrap(),
+            force_claim_curve: None,
+            fee_pool_ratio: None,
+            fee_burn_ratio: None,
             fee_collector: "fee_collector".to_string(),
             max_bond_duration: 100,
+            timelock_delay: None,
         };
         let env = mock_env();
         let info = MessageInfo {
@@ -77,8 +108,8 @@ mod tests {
             Response::default()
                 .add_attribute("method", "instantiate")
                 .add_attribute("admin", "admin")
-                .add_attribute("stake_token_address", "stake_token_address")
-                .add_attribute("reward_token_address", "reward_token_address")
+                .add_attribute("stake_token", "cw20:stake_token_address")
+                .add_attribute("reward_token", "cw20:reward_token_address")
                 .add_attribute("force_claim_ratio", "0.1")
                 .add_attribute("fee_collector", "fee_collector")
         );
@@ -130,28 +161,40 @@ mod tests {
         let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         // query  staker
-        let res = query_staker_for_all_duration(deps.as_ref(), env.clone(), "staker1".to_string())
-            .unwrap();
+        let res = query_staker_for_all_duration(
+            deps.as_ref(),
+            env.clone(),
+            "staker1".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
         assert_eq!(
             res,
             StakerForAllDurationResponse {
                 positions: vec![StakerResponse {
+                    position_id: 1,
+                    owner: "staker1".to_string(),
                     staked_amount: Uint128::new(100),
-                    index: Decimal::zero().into(),
                     bond_time: Timestamp::from_nanos(1571797419879305533),
                     unbond_duration_as_days: 10,
-                    pending_rewards: Uint128::zero(),
-                    dec_rewards: Decimal::zero().into(),
                     last_claimed: Timestamp::from_nanos(1571797419879305533),
                     position_weight: Decimal256::from_str(
                         "316.2277660168379331".to_string().as_str()
                     )
                     .unwrap(),
+                    rewards: vec![PositionRewardResponse {
+                        reward_token: AssetInfo::cw20(Addr::unchecked("reward_token_address")),
+                        index: Decimal::zero().into(),
+                        pending_rewards: Uint128::zero(),
+                        dec_rewards: Decimal::zero().into(),
+                    }],
+                    auto_compound: false,
                 }]
             }
         );
 
-        // bond again with same duration and address
+        // bond again with same duration and address creates a new position
         let info = mock_info("stake_token_address", &[]);
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "staker1".to_string(),
@@ -162,15 +205,112 @@ mod tests {
         let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
         // query  staker
-        let res = query_staker_for_all_duration(deps.as_ref(), env, "staker1".to_string()).unwrap();
-        // new weight shuld be 200*(10.sqrt()) = 200*3.16 = 632
+        let res = query_staker_for_all_duration(
+            deps.as_ref(),
+            env.clone(),
+            "staker1".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        // first position is not changed
+        assert_eq!(res.positions.len(), 2);
+        assert_eq!(res.positions[0].staked_amount, Uint128::new(100));
+        assert_eq!(res.positions[1].position_id, 2);
+        assert_eq!(
+            res.positions[1].position_weight,
+            Decimal256::from_str("316.2277660168379331").unwrap()
+        );
+        assert_eq!(res.positions[1].staked_amount, Uint128::new(100));
+        assert_eq!(res.positions[1].unbond_duration_as_days, 10);
+        assert_eq!(res.positions[1].rewards[0].index, Decimal256::zero());
+
+        // total weight is the sum of both positions
+        let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+        assert_eq!(res.total_staked, Uint128::new(200));
         assert_eq!(
-            res.positions[0].position_weight,
+            res.total_weight,
             Decimal256::from_str("632.4555320336758662").unwrap()
         );
-        assert_eq!(res.positions[0].staked_amount, Uint128::new(200));
-        assert_eq!(res.positions[0].unbond_duration_as_days, 10);
-        assert_eq!(res.positions[0].index, Decimal256::zero());
+    }
+
+    #[test]
+    pub fn test_bond_native() {
+        //instantiate with native stake token
+        let mut deps = mock_dependencies();
+        let mut init_msg = default_init();
+        init_msg.stake_token = AssetInfoUnchecked::native("ustake");
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        //bond with no funds
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::Bond { duration_day: 16 };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::Payment(PaymentError::NoFunds {}));
+
+        //bond with wrong denom
+        let info = mock_info("staker1", &coins(100, "uwrong"));
+        let msg = ExecuteMsg::Bond { duration_day: 16 };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::Payment(PaymentError::MissingDenom("ustake".to_string()))
+        );
+
+        //bond with cw20 token
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidCw20TokenAddress {});
+
+        //bond with native stake token
+        let info = mock_info("staker1", &coins(100, "ustake"));
+        let msg = ExecuteMsg::Bond { duration_day: 16 };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let res = query_position(deps.as_ref(), env.clone(), 1).unwrap();
+        assert_eq!(res.staked_amount, Uint128::new(100));
+        assert_eq!(res.position_weight, Decimal256::from_str("400").unwrap());
+
+        //unbond
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::UnbondStake {
+            amount: None,
+            position_id: 1,
+        };
+        execute(deps.as_mut(), env, info, msg).unwrap();
+
+        //claim unbonded native tokens after release time
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1382400);
+        let msg = ExecuteMsg::ClaimUnbonded {
+            release_at: None,
+            limit: None,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "staker1".to_string(),
+                amount: coins(100, "ustake"),
+            })
+        );
+
+        //native bond is not possible if stake token is cw20
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), env.clone(), info, default_init()).unwrap();
+        let info = mock_info("staker1", &coins(100, "ustake"));
+        let msg = ExecuteMsg::Bond { duration_day: 16 };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(res, ContractError::NoFund {});
     }
 
     #[test]
@@ -191,7 +331,7 @@ mod tests {
         let info = mock_info("creator", &[]);
         let msg = ExecuteMsg::UpdateRewardIndex {};
         let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-        assert_eq!(res.attributes[1].value, "0".to_string());
+        assert_eq!(res.attributes[2].value, "0".to_string());
 
         // bond
         let info = mock_info("stake_token_address", &[]);
@@ -209,13 +349,17 @@ mod tests {
         env.block.time = env.block.time.plus_seconds(100);
         let res = execute(deps.as_mut(), env, info, msg).unwrap();
         // still zero because no reward per second
-        assert_eq!(res.attributes[2].value, "0".to_string());
+        assert_eq!(res.attributes[3].value, "0".to_string());
+
+        // fund rewards
+        fund_rewards(deps.as_mut());
 
         // set reward per second
         let env = mock_env();
         let info = mock_info("creator", &[]);
         let msg = ExecuteMsg::SetRewardPerSecond {
             reward_per_second: Uint128::new(100),
+            reward_token: None,
         };
         let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 
@@ -231,113 +375,123 @@ mod tests {
         let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
         println!("{:?}", res);
         assert_eq!(
-            res.global_index,
+            res.rewards[0].global_index,
             Decimal256::from_str("31.622776601683793329").unwrap()
         );
-        assert_eq!(res.total_reward_claimed, Uint128::new(10000));
+        assert_eq!(res.rewards[0].total_reward_claimed, Uint128::new(10000));
     }
 
     #[test]
-    pub fn test_update_staker_rewards() {
+    pub fn test_reward_update() {
         // instantiate
         let mut deps = mock_dependencies();
         let init_msg = default_init();
         let env = mock_env();
-        instantiate(deps.as_mut(), env, mock_info("creator", &[]), init_msg).unwrap();
-
-        // update staker rewards with no bond
-        let info = mock_info("creator", &[]);
-        let msg = ExecuteMsg::UpdateStakerRewards { address: None };
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
-        assert_eq!(res, ContractError::NoBond {});
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            init_msg,
+        )
+        .unwrap();
 
         // bond
-        let env = mock_env();
         let info = mock_info("stake_token_address", &[]);
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "staker1".to_string(),
             amount: Uint128::new(100),
-            msg: to_binary(&ReceiveMsg::Bond { duration_day: 10 }).unwrap(),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
         });
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        // set reward per second
-        let env = mock_env();
-        let info = mock_info("creator", &[]);
-        let msg = ExecuteMsg::SetRewardPerSecond {
-            reward_per_second: Uint128::new(1000),
-        };
-        execute(deps.as_mut(), env, info, msg).unwrap();
+        // fund rewards by random address
+        let info = mock_info("reward_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "random".to_string(),
+            amount: Uint128::new(1_000_000),
+            msg: to_binary(&ReceiveMsg::RewardUpdate {
+                reward_end_date: env.block.time.plus_seconds(1000),
+            })
+            .unwrap(),
+        });
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
 
-        // update staker rewards
-        let info = mock_info("staker1", &[]);
-        let msg = ExecuteMsg::UpdateStakerRewards { address: None };
-        let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(1000);
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-        // query  staker
-        let res = query_staker_for_duration(env.clone(), deps.as_ref(), "staker1".to_string(), 10)
-            .unwrap();
-        // checking if the reward distrubuted is same as pending rewards of staker
-        let reward_to_staker1 = res.pending_rewards;
-        let rounded_reward =
-            Uint128::from_str(res.dec_rewards.to_uint_ceil().to_string().as_str()).unwrap();
+        // fund rewards with wrong token
+        let info = mock_info("wrong_reward_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(1_000_000),
+            msg: to_binary(&ReceiveMsg::RewardUpdate {
+                reward_end_date: env.block.time.plus_seconds(1000),
+            })
+            .unwrap(),
+        });
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::RewardStreamNotFound {});
 
-        // query  state
-        let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
-        let reward_distrubuted = res.total_reward_claimed;
-        assert_eq!(reward_to_staker1 + rounded_reward, reward_distrubuted);
+        // fund rewards with end date in the past
+        let info = mock_info("reward_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(1_000_000),
+            msg: to_binary(&ReceiveMsg::RewardUpdate {
+                reward_end_date: env.block.time,
+            })
+            .unwrap(),
+        });
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidRewardEndTime {});
 
-        // update one staker with multiple durations
-        // second bond
-        //first 1000000 is for first bond
-        let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(1000);
-        let info = mock_info("stake_token_address", &[]);
+        // fund 1_000_000 rewards to be distributed in 1000 seconds
+        let info = mock_info("reward_token_address", &[]);
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: "staker1".to_string(),
-            amount: Uint128::new(100),
-            msg: to_binary(&ReceiveMsg::Bond { duration_day: 20 }).unwrap(),
+            sender: "creator".to_string(),
+            amount: Uint128::new(1_000_000),
+            msg: to_binary(&ReceiveMsg::RewardUpdate {
+                reward_end_date: env.block.time.plus_seconds(1000),
+            })
+            .unwrap(),
         });
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let res = query_state(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+        assert_eq!(res.rewards[0].reward_per_second, Uint128::new(1000));
+        assert_eq!(
+            res.rewards[0].reward_end_time,
+            Some(env.block.time.plus_seconds(1000))
+        );
 
-        // update staker rewards
-        let info = mock_info("staker1", &[]);
-        let msg = ExecuteMsg::UpdateStakerRewards { address: None };
+        // at 500 seconds fund 500_000 more and extend end date to 2500 seconds
+        // remaining 500_000 + incoming 500_000 distributed in 2000 seconds
         let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(2000);
-        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-        let _rewards = res.attributes[2].value.parse::<u128>().unwrap();
-
-        // query  staker for all durations
-        let _res = query_state(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
-
-        let res = query_staker_for_all_duration(deps.as_ref(), env.clone(), "staker1".to_string())
-            .unwrap();
-
-        // query state
-        // checking if the reward distrubuted is same as pending rewards of staker
-        let reward_to_staker1 = res.positions[0].pending_rewards + res.positions[1].pending_rewards;
-        let rounded_reward = Uint128::from_str(
-            (res.positions[0].dec_rewards + res.positions[1].dec_rewards)
-                .to_uint_ceil()
-                .to_string()
-                .as_str(),
-        );
-        // query  state
+        env.block.time = env.block.time.plus_seconds(500);
+        let info = mock_info("reward_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(500_000),
+            msg: to_binary(&ReceiveMsg::RewardUpdate {
+                reward_end_date: env.block.time.plus_seconds(2000),
+            })
+            .unwrap(),
+        });
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
         let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+        assert_eq!(res.rewards[0].reward_per_second, Uint128::new(500));
 
-        let reward_distrubuted = res.total_reward_claimed;
-        assert_eq!(
-            reward_to_staker1 + rounded_reward.unwrap(),
-            reward_distrubuted
-        );
+        // update reward index after reward end date. no more rewards are distributed
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(5000);
+        let msg = ExecuteMsg::UpdateRewardIndex {};
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+        assert_eq!(res.rewards[0].total_reward_claimed, Uint128::new(1_500_000));
+        assert_eq!(res.rewards[0].total_reward_supply, Uint128::new(1_500_000));
     }
 
     #[test]
-    pub fn test_scenario() {
-        //init
-        let mut deps = mock_dependencies_with_balance(&[]);
+    pub fn test_reward_solvency() {
+        // instantiate
+        let mut deps = mock_dependencies();
         let init_msg = default_init();
         let env = mock_env();
         instantiate(
@@ -348,664 +502,720 @@ mod tests {
         )
         .unwrap();
 
-        //first bond
+        // bond
         let info = mock_info("stake_token_address", &[]);
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "staker1".to_string(),
             amount: Uint128::new(100),
             msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
         });
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        //second bond
-        let info = mock_info("stake_token_address", &[]);
+        // fund 10_000 rewards to be distributed in 1000 seconds
+        let info = mock_info("reward_token_address", &[]);
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: "staker2".to_string(),
-            amount: Uint128::new(100),
-            msg: to_binary(&ReceiveMsg::Bond { duration_day: 25 }).unwrap(),
+            sender: "creator".to_string(),
+            amount: Uint128::new(10_000),
+            msg: to_binary(&ReceiveMsg::RewardUpdate {
+                reward_end_date: env.block.time.plus_seconds(1000),
+            })
+            .unwrap(),
         });
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        //third bond
-        let info = mock_info("stake_token_address", &[]);
-        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: "staker3".to_string(),
-            amount: Uint128::new(100),
-            msg: to_binary(&ReceiveMsg::Bond { duration_day: 36 }).unwrap(),
-        });
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-        // set reward per second
+        // admin sets reward per second higher than funded rewards can afford
         let info = mock_info("creator", &[]);
         let msg = ExecuteMsg::SetRewardPerSecond {
-            reward_per_second: Uint128::from(1000u64),
+            reward_per_second: Uint128::new(100),
+            reward_token: None,
         };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        execute(deps.as_mut(), env, info, msg).unwrap();
 
-        // update staker rewards at 1000 seconds all reweards should be 100_000_000/100=1_000_000
-        // staker1 amount 100 -- duration 16 -- weight 100*4=400
-        // staker2 amount 100 -- duration 25 -- weight 100*5=500
-        // staker3 amount 100 -- duration 36 -- weight 100*6=600
-        // total weight 1500
-        // staker1 reward 400/1500*1_000_000= 266_666
-        // staker2 reward 500/1500*1_000_000= 333_333
-        // staker3 reward 600/1500*1_000_000= 400_000
+        // query runway at 50 seconds
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(50);
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RewardRunway { reward_token: None },
+        )
+        .unwrap();
+        let runway: RewardRunwayResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            runway,
+            RewardRunwayResponse {
+                reward_token: AssetInfo::cw20(Addr::unchecked("reward_token_address")),
+                total_reward_supply: Uint128::new(10_000),
+                total_reward_claimed: Uint128::new(5_000),
+                remaining_rewards: Uint128::new(5_000),
+                reward_per_second: Uint128::new(100),
+                runway_seconds: Some(50),
+                depleted_at: Some(env.block.time.plus_seconds(50)),
+            }
+        );
 
-        let info = mock_info("staker1", &[]);
+        // distribution is capped by funded rewards
         let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(1000);
-        let msg = ExecuteMsg::UpdateStakerRewards { address: None };
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        env.block.time = env.block.time.plus_seconds(200);
+        let msg = ExecuteMsg::UpdateRewardIndex {};
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let res = query_state(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+        assert_eq!(res.rewards[0].total_reward_claimed, Uint128::new(10_000));
+        assert_eq!(res.rewards[0].remaining_rewards, Uint128::zero());
 
-        // update staker 2
-        let info = mock_info("staker2", &[]);
-        let msg = ExecuteMsg::UpdateStakerRewards { address: None };
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-        // update staker 3
-        let info = mock_info("staker3", &[]);
-        let msg = ExecuteMsg::UpdateStakerRewards { address: None };
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        // staker can not receive more than funded rewards
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ReceiveReward { position_id: None };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.attributes[2].value,
+            "cw20:reward_token_address:10000".to_string()
+        );
 
-        // query staker 1
-        let res = query_staker_for_all_duration(deps.as_ref(), env.clone(), "staker1".to_string())
-            .unwrap();
-        assert_eq!(res.positions[0].pending_rewards, Uint128::new(266_666));
-        // query staker 2
-        let res = query_staker_for_all_duration(deps.as_ref(), env.clone(), "staker2".to_string())
-            .unwrap();
-        assert_eq!(res.positions[0].pending_rewards, Uint128::new(333_333));
-        // query staker 3
-        let res = query_staker_for_all_duration(deps.as_ref(), env, "staker3".to_string()).unwrap();
-        assert_eq!(res.positions[0].pending_rewards, Uint128::new(399_999));
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::RewardRunway { reward_token: None },
+        )
+        .unwrap();
+        let runway: RewardRunwayResponse = from_binary(&res).unwrap();
+        assert_eq!(runway.runway_seconds, Some(0));
     }
 
     #[test]
-    pub fn test_recieve_rewards() {
-        //init
-        let mut deps = mock_dependencies_with_balance(&[]);
-        let init_msg = default_init();
+    pub fn test_reward_runway_end_time() {
+        // instantiate
+        let mut deps = mock_dependencies();
         let env = mock_env();
-        instantiate(deps.as_mut(), env, mock_info("creator", &[]), init_msg).unwrap();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+        // 1000 rewards per second until 100_000 seconds
+        fund_rewards(deps.as_mut());
 
-        //fund rewards
-        //reward amount 100_000_000
-        //distrubuted in 100_000 seconds
-        let info = mock_info("creator", &[]);
-        let env = mock_env();
-        // set reward per second
+        // with a lowered rate the stream ends before the rewards run out. no one is staked
+        // so nothing is distributed
         let msg = ExecuteMsg::SetRewardPerSecond {
-            reward_per_second: Uint128::from(1000u64),
+            reward_per_second: Uint128::new(500),
+            reward_token: None,
         };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
-
-        //bond
-        let info = mock_info("stake_token_address", &[]);
-        let env = mock_env();
-        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: "staker1".to_string(),
-            amount: Uint128::new(100),
-            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
-        });
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
-
-        // staker 1 recieve rewards at 1000 seconds all rewards should be 100_000_000/100=1_000_000
-        let info = mock_info("staker1", &[]);
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
         let mut env = mock_env();
         env.block.time = env.block.time.plus_seconds(1000);
-        let msg = ExecuteMsg::ReceiveReward {};
-        let res = execute(deps.as_mut(), env, info, msg).unwrap();
-        assert_eq!(res.attributes[2].value, "1000000".to_string());
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RewardRunway { reward_token: None },
+        )
+        .unwrap();
+        let runway: RewardRunwayResponse = from_binary(&res).unwrap();
+        assert_eq!(runway.remaining_rewards, Uint128::new(100_000_000));
+        assert_eq!(runway.runway_seconds, Some(99_000));
         assert_eq!(
-            res.messages[0].msg,
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "reward_token_address".to_string(),
-                funds: vec![],
-                msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: "staker1".to_string(),
-                    amount: Uint128::new(1_000_000),
-                })
-                .unwrap(),
-            })
+            runway.depleted_at,
+            Some(mock_env().block.time.plus_seconds(100_000))
         );
+    }
 
-        // staker 1 bond with diffirent duration at 2000 seconds
+    #[test]
+    pub fn test_reward_schedules() {
+        // instantiate
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+
+        // bond
         let info = mock_info("stake_token_address", &[]);
-        let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(2000);
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "staker1".to_string(),
             amount: Uint128::new(100),
-            msg: to_binary(&ReceiveMsg::Bond { duration_day: 36 }).unwrap(),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
         });
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        // staker 1 recieve rewards at 3000 seconds all rewards should be 3_000_000
-        // for duration 16 it should be 2000000+400(but recieved 1000000+400)
-        // for duration 36 it should be 600
-        // total 2000000
-        let info = mock_info("staker1", &[]);
+        // schedule starting in the past
+        let info = mock_info("reward_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(10_000),
+            msg: to_binary(&ReceiveMsg::ScheduleReward {
+                start: env.block.time.minus_seconds(1),
+                end: env.block.time.plus_seconds(100),
+            })
+            .unwrap(),
+        });
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidRewardSchedule {});
+
+        // schedule ending before start
+        let info = mock_info("reward_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(10_000),
+            msg: to_binary(&ReceiveMsg::ScheduleReward {
+                start: env.block.time.plus_seconds(100),
+                end: env.block.time.plus_seconds(100),
+            })
+            .unwrap(),
+        });
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidRewardSchedule {});
+
+        // queue 10_000 rewards between 100 and 200 seconds
+        let info = mock_info("reward_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(10_000),
+            msg: to_binary(&ReceiveMsg::ScheduleReward {
+                start: env.block.time.plus_seconds(100),
+                end: env.block.time.plus_seconds(200),
+            })
+            .unwrap(),
+        });
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        // queue 20_000 rewards between 150 and 350 seconds
+        let info = mock_info("reward_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(20_000),
+            msg: to_binary(&ReceiveMsg::ScheduleReward {
+                start: env.block.time.plus_seconds(150),
+                end: env.block.time.plus_seconds(350),
+            })
+            .unwrap(),
+        });
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // both schedules are upcoming
+        let msg = QueryMsg::RewardSchedules { reward_token: None };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: RewardSchedulesResponse = from_binary(&res).unwrap();
+        assert_eq!(res.schedules.len(), 2);
+        assert!(!res.schedules[0].active);
+        assert!(!res.schedules[1].active);
+
+        // at 175 seconds both schedules are active
         let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(3000);
-        let msg = ExecuteMsg::ReceiveReward {};
-        let res = execute(deps.as_mut(), env, info, msg).unwrap();
-        assert_eq!(res.attributes[2].value, "2000000".to_string());
+        env.block.time = env.block.time.plus_seconds(175);
+        let msg = QueryMsg::RewardSchedules { reward_token: None };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: RewardSchedulesResponse = from_binary(&res).unwrap();
         assert_eq!(
-            res.messages[0].msg,
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "reward_token_address".to_string(),
-                funds: vec![],
-                msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: "staker1".to_string(),
-                    amount: Uint128::new(2_000_000),
-                })
-                .unwrap(),
-            })
+            res.schedules,
+            vec![
+                RewardScheduleResponse {
+                    reward_token: AssetInfo::cw20(Addr::unchecked("reward_token_address")),
+                    start: mock_env().block.time.plus_seconds(100),
+                    end: mock_env().block.time.plus_seconds(200),
+                    amount: Uint128::new(10_000),
+                    distributed: Uint128::new(7_500),
+                    active: true,
+                },
+                RewardScheduleResponse {
+                    reward_token: AssetInfo::cw20(Addr::unchecked("reward_token_address")),
+                    start: mock_env().block.time.plus_seconds(150),
+                    end: mock_env().block.time.plus_seconds(350),
+                    amount: Uint128::new(20_000),
+                    distributed: Uint128::new(2_500),
+                    active: true,
+                },
+            ]
         );
+
+        // rewards distributed across schedule boundaries
+        let msg = ExecuteMsg::UpdateRewardIndex {};
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+        assert_eq!(res.rewards[0].total_reward_claimed, Uint128::new(10_000));
+
+        // after every schedule is finished all scheduled rewards are distributed
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(400);
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ReceiveReward { position_id: None };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.attributes[2].value,
+            "cw20:reward_token_address:30000".to_string()
+        );
+        let res = query_state(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+        assert_eq!(res.rewards[0].total_reward_claimed, Uint128::new(30_000));
+        assert_eq!(res.rewards[0].remaining_rewards, Uint128::zero());
+        let msg = QueryMsg::RewardSchedules { reward_token: None };
+        let res = query(deps.as_ref(), env, msg).unwrap();
+        let res: RewardSchedulesResponse = from_binary(&res).unwrap();
+        assert!(res.schedules.is_empty());
     }
 
     #[test]
-    pub fn test_unbond() {
-        //init
-        let mut deps = mock_dependencies_with_balance(&[]);
-        let init_msg = default_init();
-        let env = mock_env();
-        instantiate(deps.as_mut(), env, mock_info("creator", &[]), init_msg).unwrap();
-
-        // set reward per second
-        let info = mock_info("creator", &[]);
+    pub fn test_reward_schedule_without_stakers() {
+        // instantiate
+        let mut deps = mock_dependencies();
         let env = mock_env();
-        let msg = ExecuteMsg::SetRewardPerSecond {
-            reward_per_second: Uint128::from(1000u64),
-        };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
 
-        // unbond without bond
-        let info = mock_info("staker1", &[]);
-        let env = mock_env();
-        let msg = ExecuteMsg::UnbondStake {
-            amount: None,
-            duration_as_days: 16,
-        };
-        let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
-        assert!(matches!(res, ContractError::Std(StdError::NotFound { .. })));
+        // queue 10_000 rewards between 100 and 200 seconds while no one is staked
+        let info = mock_info("reward_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(10_000),
+            msg: to_binary(&ReceiveMsg::ScheduleReward {
+                start: env.block.time.plus_seconds(100),
+                end: env.block.time.plus_seconds(200),
+            })
+            .unwrap(),
+        });
+        execute(deps.as_mut(), env, info, msg).unwrap();
 
-        // bond
+        // bond at 300 seconds, the schedule is moved to start from bonding
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(300);
         let info = mock_info("stake_token_address", &[]);
-        let env = mock_env();
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "staker1".to_string(),
             amount: Uint128::new(100),
             msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
         });
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
-
-        // unbond with amount more than bond
-        let info = mock_info("staker1", &[]);
-        let env = mock_env();
-        let msg = ExecuteMsg::UnbondStake {
-            amount: Some(Uint128::new(200)),
-            duration_as_days: 16,
-        };
-        let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
-        assert_eq!(res, ContractError::InsufficientStakedAmount {});
-
-        // unbond wrong duration
-        let info = mock_info("staker1", &[]);
-        let env = mock_env();
-        let msg = ExecuteMsg::UnbondStake {
-            amount: Some(Uint128::new(100)),
-            duration_as_days: 36,
-        };
-        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
-        assert!(matches!(res, ContractError::Std(StdError::NotFound { .. })));
-
-        // query state before unbond
-        let res = query(deps.as_ref(), env, QueryMsg::State {}).unwrap();
-        let state: StateResponse = from_binary(&res).unwrap();
-        assert_eq!(state.total_staked, Uint128::new(100));
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let msg = QueryMsg::RewardSchedules { reward_token: None };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let res: RewardSchedulesResponse = from_binary(&res).unwrap();
         assert_eq!(
-            state.total_weight,
-            Decimal256::from_str("400".to_string().as_str()).unwrap()
+            res.schedules,
+            vec![RewardScheduleResponse {
+                reward_token: AssetInfo::cw20(Addr::unchecked("reward_token_address")),
+                start: env.block.time,
+                end: env.block.time.plus_seconds(100),
+                amount: Uint128::new(10_000),
+                distributed: Uint128::zero(),
+                active: true,
+            }]
         );
 
-        // unbond
+        // whole schedule is distributed to the staker
+        env.block.time = env.block.time.plus_seconds(200);
         let info = mock_info("staker1", &[]);
-        let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(1000);
-        let msg = ExecuteMsg::UnbondStake {
-            amount: Some(Uint128::new(100)),
-            duration_as_days: 16,
-        };
+        let msg = ExecuteMsg::ReceiveReward { position_id: None };
         let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-        // at unbond rewards are recieved
-        assert_eq!(
-            res.messages[0].msg,
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "reward_token_address".to_string(),
-                funds: vec![],
-                msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: "staker1".to_string(),
-                    amount: Uint128::new(1000000),
-                })
-                .unwrap(),
-            })
-        );
-        // query state after unbond
-        let res = query(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
-        let state: StateResponse = from_binary(&res).unwrap();
-        assert_eq!(state.total_staked, Uint128::new(0));
-        assert_eq!(
-            state.total_weight,
-            Decimal256::from_str("0".to_string().as_str()).unwrap()
-        );
-
-        // query claim after unbond
-        let res = query(
-            deps.as_ref(),
-            env,
-            QueryMsg::ListClaims {
-                address: "staker1".to_string(),
-            },
-        );
-        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
-        assert_eq!(claims.claims.len(), 1);
-        assert_eq!(claims.claims[0].amount, Uint128::new(100));
-        assert_eq!(
-            claims.claims[0].release_at,
-            Timestamp::from_nanos(1573180819879305533)
-        );
         assert_eq!(
-            claims.claims[0].unbond_at,
-            Timestamp::from_nanos(1571798419879305533)
+            res.attributes[2].value,
+            "cw20:reward_token_address:10000".to_string()
         );
+        let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+        assert_eq!(res.rewards[0].remaining_rewards, Uint128::zero());
     }
 
     #[test]
-    pub fn test_claim_unbond() {
-        // init
-        let mut deps = mock_dependencies_with_balance(&[]);
+    pub fn test_update_staker_rewards() {
+        // instantiate
+        let mut deps = mock_dependencies();
         let init_msg = default_init();
         let env = mock_env();
+        instantiate(deps.as_mut(), env, mock_info("creator", &[]), init_msg).unwrap();
+
+        // update staker rewards with no bond
         let info = mock_info("creator", &[]);
-        instantiate(deps.as_mut(), env, info, init_msg).unwrap();
+        let msg = ExecuteMsg::UpdateStakerRewards {
+            address: None,
+            position_id: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::NoBond {});
 
         // bond
-        let info = mock_info("stake_token_address", &[]);
         let env = mock_env();
+        let info = mock_info("stake_token_address", &[]);
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "staker1".to_string(),
             amount: Uint128::new(100),
-            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 10 }).unwrap(),
         });
         let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 
+        // fund rewards
+        fund_rewards(deps.as_mut());
+
         // set reward per second
-        let info = mock_info("creator", &[]);
         let env = mock_env();
+        let info = mock_info("creator", &[]);
         let msg = ExecuteMsg::SetRewardPerSecond {
-            reward_per_second: Uint128::from(1000u64),
+            reward_per_second: Uint128::new(1000),
+            reward_token: None,
         };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        execute(deps.as_mut(), env, info, msg).unwrap();
 
-        // try claiming before unbond
+        // update staker rewards
         let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::UpdateStakerRewards {
+            address: None,
+            position_id: None,
+        };
         let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(1500);
-        let msg = ExecuteMsg::ClaimUnbonded {};
-        let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
-        assert_eq!(res, ContractError::NoMatureClaim {});
+        env.block.time = env.block.time.plus_seconds(1000);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        // query  staker
+        let res = query_position(deps.as_ref(), env.clone(), 1).unwrap();
+        // checking if the reward distrubuted is same as pending rewards of staker
+        let reward_to_staker1 = res.rewards[0].pending_rewards;
+        let rounded_reward = Uint128::from_str(
+            res.rewards[0]
+                .dec_rewards
+                .to_uint_ceil()
+                .to_string()
+                .as_str(),
+        )
+        .unwrap();
 
-        // unbond
-        let info = mock_info("staker1", &[]);
+        // query  state
+        let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+        let reward_distrubuted = res.rewards[0].total_reward_claimed;
+        assert_eq!(reward_to_staker1 + rounded_reward, reward_distrubuted);
+
+        // update one staker with multiple durations
+        // second bond
+        //first 1000000 is for first bond
         let mut env = mock_env();
         env.block.time = env.block.time.plus_seconds(1000);
-
-        let msg = ExecuteMsg::UnbondStake {
-            amount: Some(Uint128::new(100)),
-            duration_as_days: 16,
-        };
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 20 }).unwrap(),
+        });
         let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 
-        // try claiming before release time
+        // update staker rewards
         let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::UpdateStakerRewards {
+            address: None,
+            position_id: None,
+        };
         let mut env = mock_env();
         env.block.time = env.block.time.plus_seconds(2000);
-        let msg = ExecuteMsg::ClaimUnbonded {};
-        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
-        assert_eq!(res, ContractError::NoMatureClaim {});
-        //list claims before claim
-        let res = query(
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let _rewards = res.attributes[2]
+            .value
+            .strip_prefix("cw20:reward_token_address:")
+            .unwrap()
+            .parse::<u128>()
+            .unwrap();
+
+        // query  staker for all durations
+        let _res = query_state(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+
+        let res = query_staker_for_all_duration(
             deps.as_ref(),
-            env,
-            QueryMsg::ListClaims {
-                address: "staker1".to_string(),
-            },
+            env.clone(),
+            "staker1".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // query state
+        // checking if the reward distrubuted is same as pending rewards of staker
+        let reward_to_staker1 = res.positions[0].rewards[0].pending_rewards
+            + res.positions[1].rewards[0].pending_rewards;
+        let rounded_reward = Uint128::from_str(
+            (res.positions[0].rewards[0].dec_rewards + res.positions[1].rewards[0].dec_rewards)
+                .to_uint_ceil()
+                .to_string()
+                .as_str(),
         );
-        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
-        assert_eq!(claims.claims.len(), 1);
-        // claim
-        let info = mock_info("staker1", &[]);
-        let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(1382400 + 1000);
-        let msg = ExecuteMsg::ClaimUnbonded {};
+        // query  state
+        let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
 
-        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-        assert_eq!(res.messages.len(), 1);
+        let reward_distrubuted = res.rewards[0].total_reward_claimed;
         assert_eq!(
-            res.messages[0].msg,
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "stake_token_address".to_string(),
-                funds: vec![],
-                msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: "staker1".to_string(),
-                    amount: Uint128::new(100),
-                })
-                .unwrap(),
-            })
-        );
-        // list claim
-        let res = query(
-            deps.as_ref(),
-            env,
-            QueryMsg::ListClaims {
-                address: "staker1".to_string(),
-            },
+            reward_to_staker1 + rounded_reward.unwrap(),
+            reward_distrubuted
         );
-        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
-        assert_eq!(claims.claims.len(), 0);
     }
 
     #[test]
-    pub fn test_multiple_claims() {
-        // init
-        let mut deps = mock_dependencies_with_balance(&[]);
-        let init_msg = default_init();
-        let env = mock_env();
-        let info = mock_info("creator", &[]);
-        instantiate(deps.as_mut(), env, info, init_msg).unwrap();
-
-        // bond
-        let info = mock_info("stake_token_address", &[]);
+    pub fn test_pending_rewards() {
+        // instantiate
+        let mut deps = mock_dependencies();
         let env = mock_env();
-        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: "staker1".to_string(),
-            amount: Uint128::new(100),
-            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
-        });
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+        // 1000 rewards per second
+        fund_rewards(deps.as_mut());
 
-        // set reward per second
-        let info = mock_info("creator", &[]);
-        let env = mock_env();
-        let msg = ExecuteMsg::SetRewardPerSecond {
-            reward_per_second: Uint128::from(1000u64),
+        // no positions
+        let msg = QueryMsg::PendingRewards {
+            address: "staker1".to_string(),
+            start_after: None,
+            limit: None,
         };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let res: PendingRewardsResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert!(res.positions.is_empty());
+        assert!(res.total_rewards.is_empty());
 
-        // bond again
-        let info = mock_info("stake_token_address", &[]);
-        let env = mock_env();
-        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: "staker1".to_string(),
-            amount: Uint128::new(100),
-            msg: to_binary(&ReceiveMsg::Bond { duration_day: 20 }).unwrap(),
-        });
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        // bond 100 for 16 days and 100 for 36 days
+        for duration_day in [16, 36] {
+            let info = mock_info("stake_token_address", &[]);
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "staker1".to_string(),
+                amount: Uint128::new(100),
+                msg: to_binary(&ReceiveMsg::Bond { duration_day }).unwrap(),
+            });
+            execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
 
-        // unbond for first bond
-        let info = mock_info("staker1", &[]);
+        // at 1000 seconds 1_000_000 rewards are distributed by 400:600 weights
         let mut env = mock_env();
         env.block.time = env.block.time.plus_seconds(1000);
-        let msg = ExecuteMsg::UnbondStake {
-            amount: Some(Uint128::new(50)),
-            duration_as_days: 16,
+        let msg = QueryMsg::PendingRewards {
+            address: "staker1".to_string(),
+            start_after: None,
+            limit: None,
         };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let res: PendingRewardsResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        let reward_token = AssetInfo::cw20(Addr::unchecked("reward_token_address"));
+        assert_eq!(
+            res,
+            PendingRewardsResponse {
+                positions: vec![
+                    PositionPendingRewardsResponse {
+                        position_id: 1,
+                        unbond_duration_as_days: 16,
+                        rewards: vec![Asset::new(reward_token.clone(), 400_000u128)],
+                    },
+                    PositionPendingRewardsResponse {
+                        position_id: 2,
+                        unbond_duration_as_days: 36,
+                        rewards: vec![Asset::new(reward_token.clone(), 600_000u128)],
+                    },
+                ],
+                total_rewards: vec![Asset::new(reward_token, 1_000_000u128)],
+            }
+        );
 
-        // unbond for second bond
-        let info = mock_info("staker1", &[]);
-        let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(1000);
-        let msg = ExecuteMsg::UnbondStake {
-            amount: Some(Uint128::new(100)),
-            duration_as_days: 20,
-        };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        // stored rewards are not updated by the query
+        let res = query_position(deps.as_ref(), env.clone(), 1).unwrap();
+        assert_eq!(res.rewards[0].pending_rewards, Uint128::zero());
+        let res = query_state(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+        assert_eq!(res.rewards[0].total_reward_claimed, Uint128::zero());
 
-        // unbond remaining for first bond
-        let info = mock_info("staker1", &[]);
-        let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(2000);
-        let msg = ExecuteMsg::UnbondStake {
-            amount: Some(Uint128::new(50)),
-            duration_as_days: 16,
+        // paginate positions by id
+        let msg = QueryMsg::PendingRewards {
+            address: "staker1".to_string(),
+            start_after: Some(1),
+            limit: Some(1),
         };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
-
-        // claim for first bond at day 18
-        let info = mock_info("staker1", &[]);
-        let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(1382400 + 1001);
-
-        let res = query(
+        let res: PendingRewardsResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(res.positions.len(), 1);
+        assert_eq!(res.positions[0].position_id, 2);
+        let res = query_staker_for_all_duration(
             deps.as_ref(),
             env.clone(),
-            QueryMsg::ListClaims {
-                address: "staker1".to_string(),
-            },
-        );
-        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
-
-        assert_eq!(claims.claims.len(), 3);
-        assert_eq!(
-            claims.claims,
-            vec![
-                ClaimResponse {
-                    amount: Uint128::new(50),
-                    release_at: Timestamp::from_nanos(1573180819879305533),
-                    unbond_at: Timestamp::from_nanos(1571798419879305533),
-                },
-                ClaimResponse {
-                    amount: Uint128::new(50),
-                    release_at: Timestamp::from_nanos(1573181819879305533),
-                    unbond_at: Timestamp::from_nanos(1571799419879305533),
-                },
-                ClaimResponse {
-                    amount: Uint128::new(100),
-                    release_at: Timestamp::from_nanos(1573526419879305533),
-                    unbond_at: Timestamp::from_nanos(1571798419879305533),
-                },
-            ]
-        );
+            "staker1".to_string(),
+            None,
+            Some(1),
+        )
+        .unwrap();
+        assert_eq!(res.positions.len(), 1);
+        assert_eq!(res.positions[0].position_id, 1);
 
-        let msg = ExecuteMsg::ClaimUnbonded {};
-        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-        assert_eq!(res.messages.len(), 1);
-        assert_eq!(
-            res.messages[0].msg,
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "stake_token_address".to_string(),
-                funds: vec![],
-                msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: "staker1".to_string(),
-                    amount: Uint128::new(50),
-                })
-                .unwrap(),
-            })
-        );
+        // update rewards of one position
+        let msg = ExecuteMsg::UpdateStakerRewards {
+            address: None,
+            position_id: Some(2),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("staker1", &[]), msg).unwrap();
+        assert_eq!(res.attributes[2].value, "cw20:reward_token_address:600000");
+        let res = query_position(deps.as_ref(), env.clone(), 1).unwrap();
+        assert_eq!(res.rewards[0].pending_rewards, Uint128::zero());
+        let msg = ExecuteMsg::UpdateStakerRewards {
+            address: Some("staker2".to_string()),
+            position_id: Some(2),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("staker1", &[]), msg).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
 
-        // list claims now there should be 1 claim to be relased
-        let res = query(
-            deps.as_ref(),
+        // query matches the rewards that are received
+        let info = mock_info("staker1", &[]);
+        let res = execute(
+            deps.as_mut(),
             env,
-            QueryMsg::ListClaims {
-                address: "staker1".to_string(),
-            },
-        );
-        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+            info,
+            ExecuteMsg::ReceiveReward { position_id: None },
+        )
+        .unwrap();
         assert_eq!(
-            claims.claims,
-            vec![
-                ClaimResponse {
-                    amount: Uint128::new(50),
-                    release_at: Timestamp::from_nanos(1573181819879305533),
-                    unbond_at: Timestamp::from_nanos(1571799419879305533),
-                },
-                ClaimResponse {
-                    amount: Uint128::new(100),
-                    release_at: Timestamp::from_nanos(1573526419879305533),
-                    unbond_at: Timestamp::from_nanos(1571798419879305533),
-                },
-            ]
+            res.attributes[2].value,
+            "cw20:reward_token_address:1000000".to_string()
         );
     }
 
     #[test]
-
-    pub fn test_force_claim() {
-        // init
+    pub fn test_scenario() {
+        //init
         let mut deps = mock_dependencies_with_balance(&[]);
         let init_msg = default_init();
         let env = mock_env();
-        let info = mock_info("creator", &[]);
-        instantiate(deps.as_mut(), env, info, init_msg).unwrap();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            init_msg,
+        )
+        .unwrap();
 
-        // bond
+        //first bond
         let info = mock_info("stake_token_address", &[]);
-        let env = mock_env();
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: "staker1".to_string(),
             amount: Uint128::new(100),
             msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
         });
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        //second bond
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker2".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 25 }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        //third bond
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker3".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 36 }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // fund rewards
+        fund_rewards(deps.as_mut());
 
         // set reward per second
         let info = mock_info("creator", &[]);
-        let env = mock_env();
         let msg = ExecuteMsg::SetRewardPerSecond {
             reward_per_second: Uint128::from(1000u64),
+            reward_token: None,
         };
         let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 
-        // unbond
+        // update staker rewards at 1000 seconds all reweards should be 100_000_000/100=1_000_000
+        // staker1 amount 100 -- duration 16 -- weight 100*4=400
+        // staker2 amount 100 -- duration 25 -- weight 100*5=500
+        // staker3 amount 100 -- duration 36 -- weight 100*6=600
+        // total weight 1500
+        // staker1 reward 400/1500*1_000_000= 266_666
+        // staker2 reward 500/1500*1_000_000= 333_333
+        // staker3 reward 600/1500*1_000_000= 400_000
+
         let info = mock_info("staker1", &[]);
         let mut env = mock_env();
         env.block.time = env.block.time.plus_seconds(1000);
-        let msg = ExecuteMsg::UnbondStake {
-            amount: Some(Uint128::new(100)),
-            duration_as_days: 16,
+        let msg = ExecuteMsg::UpdateStakerRewards {
+            address: None,
+            position_id: None,
         };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        // force claim with wrong timestamp
-        let info = mock_info("staker1", &[]);
-        let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(1000);
-        let msg = ExecuteMsg::ForceClaim {
-            release_at: Timestamp::from_seconds(env.block.time.seconds() + 1382401),
+        // update staker 2
+        let info = mock_info("staker2", &[]);
+        let msg = ExecuteMsg::UpdateStakerRewards {
+            address: None,
+            position_id: None,
         };
-        let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
-        assert_eq!(res, ContractError::NoClaimForTimestamp {});
-
-        // force claim
-        let info = mock_info("staker1", &[]);
-        let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(1000);
-        let msg = ExecuteMsg::ForceClaim {
-            release_at: Timestamp::from_nanos(1573180819879305533),
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        // update staker 3
+        let info = mock_info("staker3", &[]);
+        let msg = ExecuteMsg::UpdateStakerRewards {
+            address: None,
+            position_id: None,
         };
-        // list claims
-        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
+        // query staker 1
+        let res = query_staker_for_all_duration(
+            deps.as_ref(),
+            env.clone(),
+            "staker1".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
         assert_eq!(
-            res.messages[0].msg,
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "stake_token_address".to_string(),
-                funds: vec![],
-                msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: "fee_collector".to_string(),
-                    amount: Uint128::new(10),
-                })
-                .unwrap(),
-            })
+            res.positions[0].rewards[0].pending_rewards,
+            Uint128::new(266_666)
         );
+        // query staker 2
+        let res = query_staker_for_all_duration(
+            deps.as_ref(),
+            env.clone(),
+            "staker2".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
         assert_eq!(
-            res.messages[1].msg,
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "stake_token_address".to_string(),
-                funds: vec![],
-                msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: "staker1".to_string(),
-                    amount: Uint128::new(90),
-                })
-                .unwrap(),
-            })
+            res.positions[0].rewards[0].pending_rewards,
+            Uint128::new(333_333)
         );
-        // list claims and check if removed
-        let res = query(
-            deps.as_ref(),
-            env,
-            QueryMsg::ListClaims {
-                address: "staker1".to_string(),
-            },
+        // query staker 3
+        let res =
+            query_staker_for_all_duration(deps.as_ref(), env, "staker3".to_string(), None, None)
+                .unwrap();
+        assert_eq!(
+            res.positions[0].rewards[0].pending_rewards,
+            Uint128::new(399_999)
         );
-        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
-        assert_eq!(claims.claims.len(), 0);
     }
 
     #[test]
-
-    pub fn test_update_config() {
-        // init
-        let mut deps = mock_dependencies_with_balance(&[]);
-        let init_msg = default_init();
-        let env = mock_env();
-        let info = mock_info("creator", &[]);
-        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
-
-        // update config by random address
-        let info = mock_info("random", &[]);
-        let msg = ExecuteMsg::UpdateConfig {
-            force_claim_ratio: None,
-            admin: None,
-            fee_collector: None,
-        };
-        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
-        assert_eq!(res, ContractError::Unauthorized {});
-
-        // update config
-        let info = mock_info("creator", &[]);
-        let msg = ExecuteMsg::UpdateConfig {
-            force_claim_ratio: Some(Decimal::percent(20)),
-            admin: Some("admin2".to_string()),
-            fee_collector: Some("fee_collector2".to_string()),
-        };
-        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-
-        // check config
-        let config = query(deps.as_ref(), env, QueryMsg::Config {}).unwrap();
-        let config: ConfigResponse = from_binary(&config).unwrap();
-        assert_eq!(config.force_claim_ratio, Decimal::percent(20).to_string());
-        assert_eq!(config.admin, "admin2".to_string());
-        assert_eq!(config.fee_collector, "fee_collector2".to_string());
-    }
-    #[test]
-    pub fn test_claim_impl() {
+    pub fn test_recieve_rewards() {
         //init
         let mut deps = mock_dependencies_with_balance(&[]);
         let init_msg = default_init();
         let env = mock_env();
         instantiate(deps.as_mut(), env, mock_info("creator", &[]), init_msg).unwrap();
 
-        // set reward per second
+        //fund rewards
+        //reward amount 100_000_000
+        //distrubuted in 100_000 seconds
+        fund_rewards(deps.as_mut());
         let info = mock_info("creator", &[]);
         let env = mock_env();
+        // set reward per second
         let msg = ExecuteMsg::SetRewardPerSecond {
             reward_per_second: Uint128::from(1000u64),
+            reward_token: None,
         };
         let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 
-        // bond
+        //bond
         let info = mock_info("stake_token_address", &[]);
         let env = mock_env();
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
@@ -1014,66 +1224,2976 @@ mod tests {
             msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
         });
         let _res = execute(deps.as_mut(), env, info, msg).unwrap();
-        // bond again
-        let info = mock_info("stake_token_address", &[]);
-        let env = mock_env();
-        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
-            sender: "staker1".to_string(),
-            amount: Uint128::new(100),
-            msg: to_binary(&ReceiveMsg::Bond { duration_day: 15 }).unwrap(),
-        });
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
-        // unbond
-        let info = mock_info("staker1", &[]);
-        let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(1000);
-        let msg = ExecuteMsg::UnbondStake {
-            amount: Some(Uint128::new(100)),
-            duration_as_days: 16,
-        };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 
-        // unbond again
-        let info = mock_info("staker1", &[]);
-        let mut env = mock_env();
-        env.block.time = env.block.time.plus_seconds(1000);
-        env.block.time = env.block.time.plus_seconds(86400);
-        let msg = ExecuteMsg::UnbondStake {
-            amount: Some(Uint128::new(100)),
-            duration_as_days: 15,
-        };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
-        // force claim for 2 claims for same address but diffirent duration although same release_time
+        // staker 1 recieve rewards at 1000 seconds all rewards should be 100_000_000/100=1_000_000
         let info = mock_info("staker1", &[]);
         let mut env = mock_env();
         env.block.time = env.block.time.plus_seconds(1000);
-        let msg = ExecuteMsg::ForceClaim {
-            release_at: env.block.time.plus_seconds(86400 * 16),
-        };
+        let msg = ExecuteMsg::ReceiveReward { position_id: None };
         let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.attributes[2].value,
+            "cw20:reward_token_address:1000000".to_string()
+        );
         assert_eq!(
             res.messages[0].msg,
             CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "stake_token_address".to_string(),
+                contract_addr: "reward_token_address".to_string(),
                 funds: vec![],
                 msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: "fee_collector".to_string(),
-                    amount: Uint128::new(20),
+                    recipient: "staker1".to_string(),
+                    amount: Uint128::new(1_000_000),
                 })
                 .unwrap(),
             })
         );
+
+        // staker 1 bond with diffirent duration at 2000 seconds
+        let info = mock_info("stake_token_address", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2000);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 36 }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // staker 1 recieve rewards at 3000 seconds all rewards should be 3_000_000
+        // for duration 16 it should be 2000000+400(but recieved 1000000+400)
+        // for duration 36 it should be 600
+        // total 2000000
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(3000);
+        let msg = ExecuteMsg::ReceiveReward { position_id: None };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.attributes[2].value,
+            "cw20:reward_token_address:2000000".to_string()
+        );
         assert_eq!(
-            res.messages[1].msg,
+            res.messages[0].msg,
             CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "stake_token_address".to_string(),
+                contract_addr: "reward_token_address".to_string(),
                 funds: vec![],
                 msg: to_binary(&Cw20ExecuteMsg::Transfer {
                     recipient: "staker1".to_string(),
-                    amount: Uint128::new(180),
+                    amount: Uint128::new(2_000_000),
                 })
                 .unwrap(),
             })
         );
+
+        // receive rewards of a position of another staker
+        let info = mock_info("staker2", &[]);
+        let msg = ExecuteMsg::ReceiveReward {
+            position_id: Some(2),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        // receive rewards of only the second position at 4000 seconds
+        // 1_000_000 rewards are distributed by 400:600 weights
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(4000);
+        let msg = ExecuteMsg::ReceiveReward {
+            position_id: Some(2),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.attributes[2].value,
+            "cw20:reward_token_address:600000".to_string()
+        );
+        let res = query_position(deps.as_ref(), env, 1).unwrap();
+        assert_eq!(res.rewards[0].pending_rewards, Uint128::zero());
+    }
+
+    #[test]
+    pub fn test_recieve_rewards_native() {
+        //init with native reward token
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let mut init_msg = default_init();
+        init_msg.reward_token = AssetInfoUnchecked::native("ureward");
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            init_msg,
+        )
+        .unwrap();
+
+        //fund rewards by random address
+        let info = mock_info("random", &coins(100_000, "ureward"));
+        let msg = ExecuteMsg::RewardUpdate {
+            reward_end_date: env.block.time.plus_seconds(100),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        //fund rewards with wrong denom
+        let info = mock_info("creator", &coins(100_000, "uwrong"));
+        let msg = ExecuteMsg::RewardUpdate {
+            reward_end_date: env.block.time.plus_seconds(100),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::RewardStreamNotFound {});
+
+        //fund rewards with cw20 token
+        let info = mock_info("reward_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(100_000),
+            msg: to_binary(&ReceiveMsg::RewardUpdate {
+                reward_end_date: env.block.time.plus_seconds(100),
+            })
+            .unwrap(),
+        });
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::RewardStreamNotFound {});
+
+        //fund 100_000 native rewards to be distributed in 100 seconds
+        let info = mock_info("creator", &coins(100_000, "ureward"));
+        let msg = ExecuteMsg::RewardUpdate {
+            reward_end_date: env.block.time.plus_seconds(100),
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        //bond
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // staker 1 recieve all native rewards after reward end date
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(200);
+        let msg = ExecuteMsg::ReceiveReward { position_id: None };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "staker1".to_string(),
+                amount: coins(100_000, "ureward"),
+            })
+        );
+    }
+
+    #[test]
+    pub fn test_compound() {
+        // init with the same token for stake and reward
+        let mut deps = mock_dependencies();
+        let mut init_msg = default_init();
+        init_msg.reward_token = AssetInfoUnchecked::cw20("stake_token_address");
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            init_msg,
+        )
+        .unwrap();
+
+        // fund 100_000 rewards distributed in 100 seconds
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(100_000),
+            msg: to_binary(&ReceiveMsg::RewardUpdate {
+                reward_end_date: env.block.time.plus_seconds(100),
+            })
+            .unwrap(),
+        });
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // staker1 and staker2 bond 100 tokens for 16 days
+        for staker in ["staker1", "staker2"] {
+            let info = mock_info("stake_token_address", &[]);
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: staker.to_string(),
+                amount: Uint128::new(100),
+                msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+            });
+            execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+
+        // compound position of another staker
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::Compound { position_id: 2 };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        // nothing to compound at bond time
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::Compound { position_id: 1 };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::NoRewardToCompound {});
+
+        // staker2 enables auto compound
+        let info = mock_info("staker2", &[]);
+        let msg = ExecuteMsg::SetAutoCompound {
+            position_id: 2,
+            auto_compound: true,
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // at 10 seconds 10_000 rewards are distributed, 5_000 for each staker
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(10);
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::Compound { position_id: 1 };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(res.attributes[2].value, "5000");
+
+        let res = query_position(deps.as_ref(), env.clone(), 1).unwrap();
+        assert_eq!(res.staked_amount, Uint128::new(5_100));
+        assert_eq!(
+            res.position_weight,
+            Decimal256::from_ratio(20_400u128, 1u128)
+        );
+        assert_eq!(res.rewards[0].pending_rewards, Uint128::zero());
+        let res = query_state(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+        assert_eq!(res.total_staked, Uint128::new(5_200));
+        assert_eq!(res.total_weight, Decimal256::from_ratio(20_800u128, 1u128));
+
+        // auto compounded rewards are not sent on receive reward
+        let info = mock_info("staker2", &[]);
+        let msg = ExecuteMsg::ReceiveReward { position_id: None };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(res.attributes[3].value, "5000");
+        let res = query_position(deps.as_ref(), env.clone(), 2).unwrap();
+        assert_eq!(res.staked_amount, Uint128::new(5_100));
+        assert!(res.auto_compound);
+        let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+        assert_eq!(res.total_staked, Uint128::new(10_200));
+        assert_eq!(res.total_weight, Decimal256::from_ratio(40_800u128, 1u128));
+
+        // compound is not supported if stake token is not a reward token
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::SetAutoCompound {
+            position_id: 1,
+            auto_compound: true,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(res, ContractError::CompoundNotSupported {});
+    }
+
+    #[test]
+    pub fn test_multiple_reward_streams() {
+        //init
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+
+        //bond
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        //add reward stream by random address
+        let info = mock_info("random", &[]);
+        let msg = ExecuteMsg::AddRewardStream {
+            reward_token: AssetInfoUnchecked::native("upartner"),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        //add partner reward stream
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::AddRewardStream {
+            reward_token: AssetInfoUnchecked::native("upartner"),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::RewardStreamExists {});
+
+        //fund 100_000 reward token and 50_000 partner token to be distributed in 100 seconds
+        let info = mock_info("reward_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(100_000),
+            msg: to_binary(&ReceiveMsg::RewardUpdate {
+                reward_end_date: env.block.time.plus_seconds(100),
+            })
+            .unwrap(),
+        });
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let info = mock_info("creator", &coins(50_000, "upartner"));
+        let msg = ExecuteMsg::RewardUpdate {
+            reward_end_date: env.block.time.plus_seconds(100),
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+        assert_eq!(res.rewards.len(), 2);
+        assert_eq!(res.rewards[1].reward_token, AssetInfo::native("upartner"));
+        assert_eq!(res.rewards[1].reward_per_second, Uint128::new(500));
+
+        //staker receives both reward tokens in one transaction
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        let msg = ExecuteMsg::ReceiveReward { position_id: None };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "reward_token_address".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "staker1".to_string(),
+                    amount: Uint128::new(100_000),
+                })
+                .unwrap(),
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "staker1".to_string(),
+                amount: coins(50_000, "upartner"),
+            })
+        );
+
+        let res = query_position(deps.as_ref(), env, 1).unwrap();
+        assert_eq!(res.rewards.len(), 2);
+        assert_eq!(res.rewards[1].reward_token, AssetInfo::native("upartner"));
+        assert_eq!(res.rewards[1].pending_rewards, Uint128::zero());
+    }
+
+    #[test]
+    pub fn test_unbond() {
+        //init
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let init_msg = default_init();
+        let env = mock_env();
+        instantiate(deps.as_mut(), env, mock_info("creator", &[]), init_msg).unwrap();
+
+        // fund rewards
+        fund_rewards(deps.as_mut());
+
+        // set reward per second
+        let info = mock_info("creator", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::SetRewardPerSecond {
+            reward_per_second: Uint128::from(1000u64),
+            reward_token: None,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // unbond without bond
+        let info = mock_info("staker1", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::UnbondStake {
+            amount: None,
+            position_id: 1,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(res, ContractError::PositionNotFound {});
+
+        // bond
+        let info = mock_info("stake_token_address", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // unbond with amount more than bond
+        let info = mock_info("staker1", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::UnbondStake {
+            amount: Some(Uint128::new(200)),
+            position_id: 1,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InsufficientStakedAmount {});
+
+        // unbond position that does not exist
+        let info = mock_info("staker1", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::UnbondStake {
+            amount: Some(Uint128::new(100)),
+            position_id: 2,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::PositionNotFound {});
+
+        // query state before unbond
+        let res = query(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+        let state: StateResponse = from_binary(&res).unwrap();
+        assert_eq!(state.total_staked, Uint128::new(100));
+        assert_eq!(
+            state.total_weight,
+            Decimal256::from_str("400".to_string().as_str()).unwrap()
+        );
+
+        // unbond
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+        let msg = ExecuteMsg::UnbondStake {
+            amount: Some(Uint128::new(100)),
+            position_id: 1,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        // at unbond rewards are recieved
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "reward_token_address".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "staker1".to_string(),
+                    amount: Uint128::new(1000000),
+                })
+                .unwrap(),
+            })
+        );
+        // query state after unbond
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+        let state: StateResponse = from_binary(&res).unwrap();
+        assert_eq!(state.total_staked, Uint128::new(0));
+        assert_eq!(
+            state.total_weight,
+            Decimal256::from_str("0".to_string().as_str()).unwrap()
+        );
+
+        // query claim after unbond
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::ListClaims {
+                address: "staker1".to_string(),
+                start_after: None,
+                limit: None,
+                status: None,
+            },
+        );
+        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+        assert_eq!(claims.claims.len(), 1);
+        assert_eq!(claims.claims[0].amount, Uint128::new(100));
+        assert_eq!(
+            claims.claims[0].release_at,
+            Timestamp::from_nanos(1573180819879305533)
+        );
+        assert_eq!(
+            claims.claims[0].unbond_at,
+            Timestamp::from_nanos(1571798419879305533)
+        );
+    }
+
+    #[test]
+    pub fn test_extend_lock() {
+        // instantiate
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+        // 1000 rewards per second
+        fund_rewards(deps.as_mut());
+
+        // bond 100 for 16 days
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // shortening or keeping the duration is not allowed
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ExtendLock {
+            position_id: 1,
+            to_duration: 16,
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidLockExtension {});
+
+        // duration higher than max bond duration
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ExtendLock {
+            position_id: 1,
+            to_duration: 101,
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidBondDuration {});
+
+        // amount higher than staked amount
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ExtendLock {
+            position_id: 1,
+            to_duration: 36,
+            amount: Some(Uint128::new(101)),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InsufficientStakedAmount {});
+
+        // position of another staker
+        let info = mock_info("staker2", &[]);
+        let msg = ExecuteMsg::ExtendLock {
+            position_id: 1,
+            to_duration: 36,
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        // extend 50 to 36 days at 1000 seconds
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+        let msg = ExecuteMsg::ExtendLock {
+            position_id: 1,
+            to_duration: 36,
+            amount: Some(Uint128::new(50)),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(res.attributes[3].value, "2");
+
+        // rewards until extension are settled to the first position
+        let res = query_position(deps.as_ref(), env.clone(), 1).unwrap();
+        assert_eq!(res.staked_amount, Uint128::new(50));
+        assert_eq!(res.position_weight, Decimal256::from_str("200").unwrap());
+        assert_eq!(res.rewards[0].pending_rewards, Uint128::new(1_000_000));
+        let res = query_position(deps.as_ref(), env.clone(), 2).unwrap();
+        assert_eq!(res.staked_amount, Uint128::new(50));
+        assert_eq!(res.unbond_duration_as_days, 36);
+        assert_eq!(res.position_weight, Decimal256::from_str("300").unwrap());
+        assert_eq!(res.rewards[0].pending_rewards, Uint128::zero());
+        let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+        assert_eq!(res.total_staked, Uint128::new(100));
+        assert_eq!(res.total_weight, Decimal256::from_str("500").unwrap());
+
+        // extend the rest of the first position to 64 days at 2000 seconds
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2000);
+        let msg = ExecuteMsg::ExtendLock {
+            position_id: 1,
+            to_duration: 64,
+            amount: None,
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let res = query_position(deps.as_ref(), env.clone(), 1).unwrap();
+        assert_eq!(res.unbond_duration_as_days, 64);
+        assert_eq!(res.position_weight, Decimal256::from_str("400").unwrap());
+        assert_eq!(res.rewards[0].pending_rewards, Uint128::new(1_400_000));
+        let res = query_state(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+        assert_eq!(res.total_weight, Decimal256::from_str("700").unwrap());
+
+        // both positions receive their rewards
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ReceiveReward { position_id: None };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.attributes[2].value,
+            "cw20:reward_token_address:2000000".to_string()
+        );
+    }
+
+    #[test]
+    pub fn test_claim_unbond() {
+        // init
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let init_msg = default_init();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), env, info, init_msg).unwrap();
+
+        // bond
+        let info = mock_info("stake_token_address", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // fund rewards
+        fund_rewards(deps.as_mut());
+
+        // set reward per second
+        let info = mock_info("creator", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::SetRewardPerSecond {
+            reward_per_second: Uint128::from(1000u64),
+            reward_token: None,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // try claiming before unbond
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1500);
+        let msg = ExecuteMsg::ClaimUnbonded {
+            release_at: None,
+            limit: None,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(res, ContractError::NoMatureClaim {});
+
+        // unbond
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+
+        let msg = ExecuteMsg::UnbondStake {
+            amount: Some(Uint128::new(100)),
+            position_id: 1,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // try claiming before release time
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2000);
+        let msg = ExecuteMsg::ClaimUnbonded {
+            release_at: None,
+            limit: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::NoMatureClaim {});
+        //list claims before claim
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::ListClaims {
+                address: "staker1".to_string(),
+                start_after: None,
+                limit: None,
+                status: None,
+            },
+        );
+        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+        assert_eq!(claims.claims.len(), 1);
+        // claim
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1382400 + 1000);
+        let msg = ExecuteMsg::ClaimUnbonded {
+            release_at: None,
+            limit: None,
+        };
+
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "stake_token_address".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "staker1".to_string(),
+                    amount: Uint128::new(100),
+                })
+                .unwrap(),
+            })
+        );
+        // list claim
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::ListClaims {
+                address: "staker1".to_string(),
+                start_after: None,
+                limit: None,
+                status: None,
+            },
+        );
+        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+        assert_eq!(claims.claims.len(), 0);
+    }
+
+    #[test]
+    pub fn test_rebond() {
+        // instantiate
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+        // 1000 rewards per second
+        fund_rewards(deps.as_mut());
+
+        // bond 100 for 16 days
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // unbond at 1000 seconds
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+        let msg = ExecuteMsg::UnbondStake {
+            amount: None,
+            position_id: 1,
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let release_at = env.block.time.plus_seconds(16 * 86400);
+
+        // rebond with wrong release time
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2000);
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::Rebond {
+            release_at: release_at.plus_seconds(1),
+            duration_as_days: 25,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::NoClaimForTimestamp {});
+
+        // rebond with invalid duration
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::Rebond {
+            release_at,
+            duration_as_days: 101,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidBondDuration {});
+
+        // can not rebond for a shorter duration than the claim was unbonding from
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::Rebond {
+            release_at,
+            duration_as_days: 15,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidRebondDuration {});
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::Rebond {
+            release_at,
+            duration_as_days: 1,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidRebondDuration {});
+
+        // rebond claim for 25 days at 2000 seconds
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::Rebond {
+            release_at,
+            duration_as_days: 25,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(res.attributes[2].value, "2");
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ListClaims {
+                address: "staker1".to_string(),
+                start_after: None,
+                limit: None,
+                status: None,
+            },
+        );
+        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+        assert!(claims.claims.is_empty());
+        let res = query_position(deps.as_ref(), env.clone(), 2).unwrap();
+        assert_eq!(res.staked_amount, Uint128::new(100));
+        assert_eq!(res.unbond_duration_as_days, 25);
+        assert_eq!(res.bond_time, env.block.time);
+        assert_eq!(res.position_weight, Decimal256::from_str("500").unwrap());
+        assert_eq!(res.rewards[0].pending_rewards, Uint128::zero());
+        let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+        assert_eq!(res.total_staked, Uint128::new(100));
+        assert_eq!(res.total_weight, Decimal256::from_str("500").unwrap());
+
+        // rebonded position earns rewards from rebond time
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(3000);
+        let msg = ExecuteMsg::ReceiveReward {
+            position_id: Some(2),
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.attributes[2].value,
+            "cw20:reward_token_address:1000000".to_string()
+        );
+    }
+
+    #[test]
+    pub fn test_multiple_claims() {
+        // init
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let init_msg = default_init();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), env, info, init_msg).unwrap();
+
+        // bond
+        let info = mock_info("stake_token_address", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // fund rewards
+        fund_rewards(deps.as_mut());
+
+        // set reward per second
+        let info = mock_info("creator", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::SetRewardPerSecond {
+            reward_per_second: Uint128::from(1000u64),
+            reward_token: None,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // bond again
+        let info = mock_info("stake_token_address", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 20 }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // unbond for first bond
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+        let msg = ExecuteMsg::UnbondStake {
+            amount: Some(Uint128::new(50)),
+            position_id: 1,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // unbond for second bond
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+        let msg = ExecuteMsg::UnbondStake {
+            amount: Some(Uint128::new(100)),
+            position_id: 2,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // unbond remaining for first bond
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(2000);
+        let msg = ExecuteMsg::UnbondStake {
+            amount: Some(Uint128::new(50)),
+            position_id: 1,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // claim for first bond at day 18
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1382400 + 1001);
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ListClaims {
+                address: "staker1".to_string(),
+                start_after: None,
+                limit: None,
+                status: None,
+            },
+        );
+        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+
+        assert_eq!(claims.claims.len(), 3);
+        assert_eq!(
+            claims.claims,
+            vec![
+                ClaimResponse {
+                    id: 1,
+                    amount: Uint128::new(50),
+                    release_at: Timestamp::from_nanos(1573180819879305533),
+                    unbond_at: Timestamp::from_nanos(1571798419879305533),
+                },
+                ClaimResponse {
+                    id: 1,
+                    amount: Uint128::new(50),
+                    release_at: Timestamp::from_nanos(1573181819879305533),
+                    unbond_at: Timestamp::from_nanos(1571799419879305533),
+                },
+                ClaimResponse {
+                    id: 1,
+                    amount: Uint128::new(100),
+                    release_at: Timestamp::from_nanos(1573526419879305533),
+                    unbond_at: Timestamp::from_nanos(1571798419879305533),
+                },
+            ]
+        );
+        // first claim is mature, other two are pending
+        assert_eq!(
+            claims.summary,
+            ClaimsSummary {
+                total_unbonding: Uint128::new(200),
+                claim_count: 3,
+                next_release_at: Some(Timestamp::from_nanos(1573181819879305533)),
+            }
+        );
+
+        // paginate after the first claim
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ListClaims {
+                address: "staker1".to_string(),
+                start_after: Some((Timestamp::from_nanos(1573180819879305533), 1)),
+                limit: Some(1),
+                status: None,
+            },
+        );
+        let page: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+        assert_eq!(page.claims, vec![claims.claims[1].clone()]);
+        // summary covers every claim of the address
+        assert_eq!(page.summary, claims.summary);
+
+        // filter by status
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ListClaims {
+                address: "staker1".to_string(),
+                start_after: None,
+                limit: None,
+                status: Some(ClaimStatus::Mature),
+            },
+        );
+        let mature: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+        assert_eq!(mature.claims, vec![claims.claims[0].clone()]);
+        assert_eq!(mature.summary, claims.summary);
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ListClaims {
+                address: "staker1".to_string(),
+                start_after: None,
+                limit: None,
+                status: Some(ClaimStatus::Pending),
+            },
+        );
+        let pending: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+        assert_eq!(pending.claims, claims.claims[1..].to_vec());
+        // status filter is combined with pagination
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ListClaims {
+                address: "staker1".to_string(),
+                start_after: Some((Timestamp::from_nanos(1573181819879305533), 1)),
+                limit: None,
+                status: Some(ClaimStatus::Pending),
+            },
+        );
+        let pending: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+        assert_eq!(pending.claims, vec![claims.claims[2].clone()]);
+
+        let msg = ExecuteMsg::ClaimUnbonded {
+            release_at: None,
+            limit: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "stake_token_address".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "staker1".to_string(),
+                    amount: Uint128::new(50),
+                })
+                .unwrap(),
+            })
+        );
+
+        // list claims now there should be 1 claim to be relased
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::ListClaims {
+                address: "staker1".to_string(),
+                start_after: None,
+                limit: None,
+                status: None,
+            },
+        );
+        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+        assert_eq!(
+            claims.claims,
+            vec![
+                ClaimResponse {
+                    id: 1,
+                    amount: Uint128::new(50),
+                    release_at: Timestamp::from_nanos(1573181819879305533),
+                    unbond_at: Timestamp::from_nanos(1571799419879305533),
+                },
+                ClaimResponse {
+                    id: 1,
+                    amount: Uint128::new(100),
+                    release_at: Timestamp::from_nanos(1573526419879305533),
+                    unbond_at: Timestamp::from_nanos(1571798419879305533),
+                },
+            ]
+        );
+        // claimed amount is removed from the totals
+        assert_eq!(
+            claims.summary,
+            ClaimsSummary {
+                total_unbonding: Uint128::new(150),
+                claim_count: 2,
+                next_release_at: Some(Timestamp::from_nanos(1573181819879305533)),
+            }
+        );
+    }
+
+    #[test]
+
+    pub fn test_force_claim() {
+        // init
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let init_msg = default_init();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), env, info, init_msg).unwrap();
+
+        // bond
+        let info = mock_info("stake_token_address", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // fund rewards
+        fund_rewards(deps.as_mut());
+
+        // set reward per second
+        let info = mock_info("creator", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::SetRewardPerSecond {
+            reward_per_second: Uint128::from(1000u64),
+            reward_token: None,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // unbond
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+        let msg = ExecuteMsg::UnbondStake {
+            amount: Some(Uint128::new(100)),
+            position_id: 1,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // force claim with wrong timestamp
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+        let msg = ExecuteMsg::ForceClaim {
+            release_at: Timestamp::from_seconds(env.block.time.seconds() + 1382401),
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(res, ContractError::NoClaimForTimestamp {});
+
+        // force claim
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+        let msg = ExecuteMsg::ForceClaim {
+            release_at: Timestamp::from_nanos(1573180819879305533),
+            amount: None,
+        };
+        // list claims
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "stake_token_address".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "fee_collector".to_string(),
+                    amount: Uint128::new(10),
+                })
+                .unwrap(),
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "stake_token_address".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "staker1".to_string(),
+                    amount: Uint128::new(90),
+                })
+                .unwrap(),
+            })
+        );
+        // list claims and check if removed
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::ListClaims {
+                address: "staker1".to_string(),
+                start_after: None,
+                limit: None,
+                status: None,
+            },
+        );
+        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+        assert_eq!(claims.claims.len(), 0);
+    }
+
+    #[test]
+    pub fn test_partial_force_claim() {
+        // init
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+
+        // bond 100 and 200 for 16 days
+        for amount in [100u128, 200] {
+            let info = mock_info("stake_token_address", &[]);
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "staker1".to_string(),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+            });
+            execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+        // unbond both positions at the same time so they share the release time
+        for position_id in [1, 2] {
+            let info = mock_info("staker1", &[]);
+            let msg = ExecuteMsg::UnbondStake {
+                amount: None,
+                position_id,
+            };
+            execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+        let release_at = env.block.time.plus_seconds(16 * 86400);
+
+        // zero amount
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ForceClaim {
+            release_at,
+            amount: Some(Uint128::zero()),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidForceClaimAmount {});
+
+        // amount higher than claims
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ForceClaim {
+            release_at,
+            amount: Some(Uint128::new(301)),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidForceClaimAmount {});
+
+        // force claim 150 at half of unbonding duration
+        // all of the first claim and 50 of the second claim with 5% fee
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(8 * 86400);
+
+        // simulate force claim before executing
+        let msg = QueryMsg::SimulateForceClaim {
+            address: "staker1".to_string(),
+            release_at: release_at.plus_seconds(1),
+            amount: None,
+        };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap_err();
+        assert_eq!(
+            res,
+            StdError::generic_err(ContractError::NoClaimForTimestamp {}.to_string())
+        );
+        let msg = QueryMsg::SimulateForceClaim {
+            address: "staker1".to_string(),
+            release_at,
+            amount: Some(Uint128::new(150)),
+        };
+        let res: SimulateForceClaimResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(
+            res,
+            SimulateForceClaimResponse {
+                claims: vec![
+                    ForceClaimSimulationResponse {
+                        release_at,
+                        unbond_at: mock_env().block.time,
+                        amount: Uint128::new(100),
+                        fee: Uint128::new(5),
+                        net_amount: Uint128::new(95),
+                    },
+                    ForceClaimSimulationResponse {
+                        release_at,
+                        unbond_at: mock_env().block.time,
+                        amount: Uint128::new(50),
+                        fee: Uint128::new(2),
+                        net_amount: Uint128::new(48),
+                    },
+                ],
+                total_amount: Uint128::new(150),
+                total_fee: Uint128::new(7),
+                total_net_amount: Uint128::new(143),
+            }
+        );
+
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ForceClaim {
+            release_at,
+            amount: Some(Uint128::new(150)),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "143");
+        assert_eq!(res.attributes[2].value, "7");
+
+        // remainder of the second claim is kept with its original times
+        let res = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::ListClaims {
+                address: "staker1".to_string(),
+                start_after: None,
+                limit: None,
+                status: None,
+            },
+        );
+        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+        assert_eq!(
+            claims.claims,
+            vec![ClaimResponse {
+                id: 2,
+                amount: Uint128::new(150),
+                release_at,
+                unbond_at: mock_env().block.time,
+            }]
+        );
+        assert_eq!(claims.summary.total_unbonding, Uint128::new(150));
+        assert_eq!(claims.summary.claim_count, 1);
+    }
+
+    #[test]
+    pub fn test_force_claim_curves() {
+        // init with invalid curve
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let mut init_msg = default_init();
+        init_msg.force_claim_curve = Some(FeeCurve::ExponentialDecay { half_life: 0 });
+        let res = instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            init_msg,
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::InvalidFeeCurve {});
+
+        // init with default linear curve and 10% ratio
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+        let config: ConfigResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(config.force_claim_curve, FeeCurve::Linear {});
+
+        // unbonding of 20 days started 5 days ago
+        let unbond_at = env.block.time.minus_seconds(5 * 86400);
+        let release_at = unbond_at.plus_seconds(20 * 86400);
+        let fee_query = |deps: &cosmwasm_std::OwnedDeps<_, _, _>| -> ForceClaimFeeResponse {
+            let msg = QueryMsg::ForceClaimFee {
+                amount: Uint128::new(1000),
+                unbond_at,
+                release_at,
+            };
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+        };
+        // linear: 10% * 15 / 20
+        assert_eq!(
+            fee_query(&deps),
+            ForceClaimFeeResponse {
+                fee_ratio: Decimal::from_str("0.075").unwrap(),
+                fee: Uint128::new(75),
+                net_amount: Uint128::new(925),
+            }
+        );
+
+        // flat: 10% until release
+        let update_curve = |deps: DepsMut, curve: FeeCurve| {
+            let msg = ExecuteMsg::UpdateConfig {
+                fee_collector: None,
+                force_claim_ratio: None,
+                force_claim_curve: Some(curve),
+                fee_pool_ratio: None,
+                fee_burn_ratio: None,
+                max_bond_duration: None,
+            };
+            execute(deps, mock_env(), mock_info("creator", &[]), msg)
+        };
+        update_curve(deps.as_mut(), FeeCurve::Flat {}).unwrap();
+        assert_eq!(fee_query(&deps).fee, Uint128::new(100));
+
+        // exponential decay: halved every 2 days, 5 days is 2.5 half lives
+        // 10% / 4 * (1 - 0.5 / 2) = 1.875%
+        update_curve(
+            deps.as_mut(),
+            FeeCurve::ExponentialDecay {
+                half_life: 2 * 86400,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            fee_query(&deps).fee_ratio,
+            Decimal::from_str("0.01875").unwrap()
+        );
+
+        // tiered: 10% in the first week and 5% after
+        let tiers = vec![
+            FeeTier {
+                start_after: 0,
+                ratio: Decimal::percent(10),
+            },
+            FeeTier {
+                start_after: 7 * 86400,
+                ratio: Decimal::percent(5),
+            },
+        ];
+        update_curve(
+            deps.as_mut(),
+            FeeCurve::Tiered {
+                tiers: tiers.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(fee_query(&deps).fee, Uint128::new(100));
+
+        // tiers must start at unbonding, be sorted and have ratios lower than 1
+        for invalid_tiers in [
+            vec![],
+            tiers[1..].to_vec(),
+            vec![tiers[0].clone(), tiers[0].clone()],
+            vec![FeeTier {
+                start_after: 0,
+                ratio: Decimal::one(),
+            }],
+        ] {
+            let res = update_curve(
+                deps.as_mut(),
+                FeeCurve::Tiered {
+                    tiers: invalid_tiers,
+                },
+            )
+            .unwrap_err();
+            assert_eq!(res, ContractError::InvalidFeeCurve {});
+        }
+
+        // force claim uses the tiered curve after the first week
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(1000),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 20 }).unwrap(),
+        });
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::UnbondStake {
+            amount: None,
+            position_id: 1,
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(8 * 86400);
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ForceClaim {
+            release_at: mock_env().block.time.plus_seconds(20 * 86400),
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "950");
+        assert_eq!(res.attributes[2].value, "50");
+    }
+
+    #[test]
+    pub fn test_force_claim_fee_to_stakers() {
+        // init with half of force claim fees distributed to stakers
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let mut init_msg = default_init();
+        init_msg.fee_pool_ratio = Some(Decimal::percent(101));
+        let res = instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            init_msg,
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::InvalidFeePoolRatio {});
+        let mut init_msg = default_init();
+        init_msg.fee_pool_ratio = Some(Decimal::percent(50));
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            init_msg,
+        )
+        .unwrap();
+
+        // staker1 and staker2 bond 1000 for 16 days
+        for staker in ["staker1", "staker2"] {
+            let info = mock_info("stake_token_address", &[]);
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: staker.to_string(),
+                amount: Uint128::new(1000),
+                msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+            });
+            execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+
+        // staker1 unbonds and force claims at once with 10% fee
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::UnbondStake {
+            amount: None,
+            position_id: 1,
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ForceClaim {
+            release_at: env.block.time.plus_seconds(16 * 86400),
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(res.attributes[3].value, "50");
+        assert_eq!(res.attributes[5].value, "50");
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "stake_token_address".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "fee_collector".to_string(),
+                    amount: Uint128::new(50),
+                })
+                .unwrap(),
+            })
+        );
+
+        // stake token reward stream is created and pool share is distributed to staker2
+        let res = query_state(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+        assert_eq!(res.rewards.len(), 2);
+        assert_eq!(
+            res.rewards[1].reward_token,
+            AssetInfo::cw20(Addr::unchecked("stake_token_address"))
+        );
+        assert_eq!(res.rewards[1].total_reward_supply, Uint128::new(50));
+        let info = mock_info("staker2", &[]);
+        let msg = ExecuteMsg::ReceiveReward { position_id: None };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "stake_token_address".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "staker2".to_string(),
+                    amount: Uint128::new(50),
+                })
+                .unwrap(),
+            })
+        );
+
+        // whole fee goes to fee collector if there are no stakers left
+        let info = mock_info("staker2", &[]);
+        let msg = ExecuteMsg::UnbondStake {
+            amount: None,
+            position_id: 2,
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let info = mock_info("staker2", &[]);
+        let msg = ExecuteMsg::ForceClaim {
+            release_at: env.block.time.plus_seconds(16 * 86400),
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(res.attributes[3].value, "0");
+        assert_eq!(res.attributes[5].value, "100");
+    }
+
+    #[test]
+    pub fn test_force_claim_fee_burn() {
+        // burn and pool ratios can not exceed 1 together
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let mut init_msg = default_init();
+        init_msg.fee_pool_ratio = Some(Decimal::percent(50));
+        init_msg.fee_burn_ratio = Some(Decimal::percent(60));
+        let res = instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            init_msg,
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::InvalidFeeBurnRatio {});
+
+        // init with native stake token and 40% of force claim fees burned
+        let mut init_msg = default_init();
+        init_msg.stake_token = AssetInfoUnchecked::native("ustake");
+        init_msg.fee_burn_ratio = Some(Decimal::percent(40));
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            init_msg,
+        )
+        .unwrap();
+
+        // pool ratio update is checked against the burn ratio
+        let msg = ExecuteMsg::UpdateConfig {
+            fee_collector: None,
+            force_claim_ratio: None,
+            force_claim_curve: None,
+            fee_pool_ratio: Some(Decimal::percent(70)),
+            fee_burn_ratio: None,
+            max_bond_duration: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidFeeBurnRatio {});
+
+        // bond, unbond and force claim at once with 10% fee
+        let info = mock_info("staker1", &coins(1000, "ustake"));
+        let msg = ExecuteMsg::Bond { duration_day: 16 };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::UnbondStake {
+            amount: None,
+            position_id: 1,
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ForceClaim {
+            release_at: env.block.time.plus_seconds(16 * 86400),
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(res.attributes[4].value, "40");
+        assert_eq!(res.attributes[5].value, "60");
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Burn {
+                amount: coins(40, "ustake"),
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "fee_collector".to_string(),
+                amount: coins(60, "ustake"),
+            })
+        );
+        let res = query_state(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+        assert_eq!(res.total_fees_burned, Uint128::new(40));
+        assert_eq!(res.total_fees_collected, Uint128::new(60));
+
+        // whole fee is burned with a cw20 stake token
+        let mut deps = mock_dependencies();
+        let mut init_msg = default_init();
+        init_msg.fee_burn_ratio = Some(Decimal::one());
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            init_msg,
+        )
+        .unwrap();
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(1000),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::UnbondStake {
+            amount: None,
+            position_id: 1,
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ForceClaim {
+            release_at: env.block.time.plus_seconds(16 * 86400),
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "stake_token_address".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Burn {
+                    amount: Uint128::new(100),
+                })
+                .unwrap(),
+            })
+        );
+    }
+
+    #[test]
+
+    pub fn test_update_config() {
+        // init
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let init_msg = default_init();
+        let env = mock_env();
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        // update config by random address
+        let info = mock_info("random", &[]);
+        let msg = ExecuteMsg::UpdateConfig {
+            force_claim_ratio: None,
+            force_claim_curve: None,
+            fee_pool_ratio: None,
+            fee_burn_ratio: None,
+            max_bond_duration: None,
+            fee_collector: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        // update config
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::UpdateConfig {
+            force_claim_ratio: Some(Decimal::percent(20)),
+            force_claim_curve: None,
+            fee_pool_ratio: None,
+            fee_burn_ratio: None,
+            max_bond_duration: Some(20),
+            fee_collector: Some("fee_collector2".to_string()),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        // every changed field is reported
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "update_config"),
+                attr("force_claim_ratio", "0.2"),
+                attr("fee_collector", "fee_collector2"),
+                attr("max_bond_duration", "20"),
+            ]
+        );
+
+        // check config
+        let config = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+        let config: ConfigResponse = from_binary(&config).unwrap();
+        assert_eq!(config.force_claim_ratio, Decimal::percent(20).to_string());
+        assert_eq!(config.admin, "creator".to_string());
+        assert_eq!(config.fee_collector, "fee_collector2".to_string());
+        assert_eq!(config.max_bond_duration, 20);
+
+        // values are validated like instantiate
+        let update = |force_claim_ratio: Option<Decimal>, max_bond_duration: Option<u128>| {
+            ExecuteMsg::UpdateConfig {
+                force_claim_ratio,
+                force_claim_curve: None,
+                fee_pool_ratio: None,
+                fee_burn_ratio: None,
+                max_bond_duration,
+                fee_collector: None,
+            }
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            update(Some(Decimal::one()), None),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::InvalidForceClaimRatio {});
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            update(None, Some(0)),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::InvalidMaxBondDuration {});
+
+        // fee manager can not change max bond duration
+        let msg = ExecuteMsg::GrantRole {
+            role: Role::FeeManager,
+            address: "fee_manager".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("fee_manager", &[]),
+            update(None, Some(30)),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        // bonds above the lowered max bond duration are rejected
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 30 }).unwrap(),
+        });
+        let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidBondDuration {});
+    }
+    #[test]
+    pub fn test_claim_impl() {
+        //init
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let init_msg = default_init();
+        let env = mock_env();
+        instantiate(deps.as_mut(), env, mock_info("creator", &[]), init_msg).unwrap();
+
+        // fund rewards
+        fund_rewards(deps.as_mut());
+
+        // set reward per second
+        let info = mock_info("creator", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::SetRewardPerSecond {
+            reward_per_second: Uint128::from(1000u64),
+            reward_token: None,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // bond
+        let info = mock_info("stake_token_address", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        // bond again
+        let info = mock_info("stake_token_address", &[]);
+        let env = mock_env();
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 15 }).unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        // unbond
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+        let msg = ExecuteMsg::UnbondStake {
+            amount: Some(Uint128::new(100)),
+            position_id: 1,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // unbond again
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+        env.block.time = env.block.time.plus_seconds(86400);
+        let msg = ExecuteMsg::UnbondStake {
+            amount: Some(Uint128::new(100)),
+            position_id: 2,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        // force claim for 2 claims for same address but diffirent duration although same release_time
+        let info = mock_info("staker1", &[]);
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+        let msg = ExecuteMsg::ForceClaim {
+            release_at: env.block.time.plus_seconds(86400 * 16),
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "stake_token_address".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "fee_collector".to_string(),
+                    amount: Uint128::new(20),
+                })
+                .unwrap(),
+            })
+        );
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "stake_token_address".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "staker1".to_string(),
+                    amount: Uint128::new(180),
+                })
+                .unwrap(),
+            })
+        );
+    }
+
+    #[test]
+    pub fn test_all_positions_and_stakers() {
+        // instantiate
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+
+        // staker2 bonds first, then staker1 and staker3, then staker2 again
+        for (sender, amount, duration_day) in [
+            ("staker2", 100u128, 16),
+            ("staker1", 200, 16),
+            ("staker3", 300, 36),
+            ("staker2", 400, 36),
+        ] {
+            let info = mock_info("stake_token_address", &[]);
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: sender.to_string(),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Bond { duration_day }).unwrap(),
+            });
+            execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+
+        // positions are listed by position id
+        let msg = QueryMsg::AllPositions {
+            start_after: None,
+            limit: Some(3),
+        };
+        let res: AllPositionsResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        let ids: Vec<u64> = res.positions.iter().map(|p| p.position_id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(res.positions[0].owner, "staker2".to_string());
+        assert_eq!(res.positions[1].owner, "staker1".to_string());
+
+        let msg = QueryMsg::AllPositions {
+            start_after: Some(3),
+            limit: None,
+        };
+        let res: AllPositionsResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(res.positions.len(), 1);
+        assert_eq!(res.positions[0].position_id, 4);
+        assert_eq!(res.positions[0].staked_amount, Uint128::new(400));
+
+        // stakers are listed once by address with their positions summed
+        let msg = QueryMsg::AllStakers {
+            start_after: None,
+            limit: Some(2),
+        };
+        let res: AllStakersResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(
+            res.stakers,
+            vec![
+                StakerSummaryResponse {
+                    address: "staker1".to_string(),
+                    positions: 1,
+                    staked_amount: Uint128::new(200),
+                },
+                StakerSummaryResponse {
+                    address: "staker2".to_string(),
+                    positions: 2,
+                    staked_amount: Uint128::new(500),
+                },
+            ]
+        );
+
+        let msg = QueryMsg::AllStakers {
+            start_after: Some("staker2".to_string()),
+            limit: None,
+        };
+        let res: AllStakersResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(
+            res.stakers,
+            vec![StakerSummaryResponse {
+                address: "staker3".to_string(),
+                positions: 1,
+                staked_amount: Uint128::new(300),
+            }]
+        );
+
+        // summaries follow unbonding, and addresses without positions are not listed
+        let msg = ExecuteMsg::UnbondStake {
+            amount: Some(Uint128::new(150)),
+            position_id: 4,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("staker2", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UnbondStake {
+            amount: None,
+            position_id: 2,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("staker1", &[]), msg).unwrap();
+        let msg = QueryMsg::AllStakers {
+            start_after: None,
+            limit: None,
+        };
+        let res: AllStakersResponse =
+            from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+        assert_eq!(
+            res.stakers,
+            vec![
+                StakerSummaryResponse {
+                    address: "staker2".to_string(),
+                    positions: 2,
+                    staked_amount: Uint128::new(350),
+                },
+                StakerSummaryResponse {
+                    address: "staker3".to_string(),
+                    positions: 1,
+                    staked_amount: Uint128::new(300),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_claim_unbonded_limit() {
+        // instantiate
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+
+        // bond 300 for 16 days
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(300),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // unbond 100 at 1000, 2000 and 3000 seconds
+        for seconds in [1000, 2000, 3000] {
+            let info = mock_info("staker1", &[]);
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(seconds);
+            let msg = ExecuteMsg::UnbondStake {
+                amount: Some(Uint128::new(100)),
+                position_id: 1,
+            };
+            execute(deps.as_mut(), env, info, msg).unwrap();
+        }
+
+        // every claim is mature, claim oldest two
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1382400 + 3000);
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ClaimUnbonded {
+            release_at: None,
+            limit: Some(0),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidLimit {});
+
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ClaimUnbonded {
+            release_at: None,
+            limit: Some(2),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "200");
+        assert_eq!(res.attributes[2].value, "2");
+        assert_eq!(res.attributes[3].value, "1");
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ListClaims {
+                address: "staker1".to_string(),
+                start_after: None,
+                limit: None,
+                status: None,
+            },
+        );
+        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+        assert_eq!(claims.claims.len(), 1);
+        assert_eq!(
+            claims.claims[0].release_at,
+            mock_env().block.time.plus_seconds(1382400 + 3000)
+        );
+
+        // claim the rest
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ClaimUnbonded {
+            release_at: None,
+            limit: Some(2),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "100");
+        assert_eq!(res.attributes[2].value, "1");
+        assert_eq!(res.attributes[3].value, "0");
+
+        // nothing left to claim
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ClaimUnbonded {
+            release_at: None,
+            limit: Some(2),
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(res, ContractError::NoMatureClaim {});
+    }
+
+    #[test]
+    pub fn test_claim_unbonded_remaining_count() {
+        // instantiate
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+
+        // 102 claims of 10
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(1020),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        for _ in 0..102 {
+            let msg = ExecuteMsg::UnbondStake {
+                amount: Some(Uint128::new(10)),
+                position_id: 1,
+            };
+            execute(deps.as_mut(), env.clone(), mock_info("staker1", &[]), msg).unwrap();
+        }
+
+        // remaining mature claims are counted up to 100
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1382400);
+        let msg = ExecuteMsg::ClaimUnbonded {
+            release_at: None,
+            limit: Some(1),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("staker1", &[]), msg).unwrap();
+        assert_eq!(res.attributes[2].value, "1");
+        assert_eq!(res.attributes[3].value, "100");
+        let msg = ExecuteMsg::ClaimUnbonded {
+            release_at: None,
+            limit: Some(2),
+        };
+        let res = execute(deps.as_mut(), env, mock_info("staker1", &[]), msg).unwrap();
+        assert_eq!(res.attributes[3].value, "99");
+    }
+
+    #[test]
+    pub fn test_claim_unbonded_release_at() {
+        // instantiate
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+
+        // bond 300 for 16 days
+        let info = mock_info("stake_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "staker1".to_string(),
+            amount: Uint128::new(300),
+            msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+        });
+        execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // unbond 50 twice at 1000 seconds and 200 at 2000 seconds
+        for (seconds, amount) in [(1000, 50u128), (1000, 50), (2000, 200)] {
+            let info = mock_info("staker1", &[]);
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(seconds);
+            let msg = ExecuteMsg::UnbondStake {
+                amount: Some(Uint128::new(amount)),
+                position_id: 1,
+            };
+            execute(deps.as_mut(), env, info, msg).unwrap();
+        }
+        let first_release = mock_env().block.time.plus_seconds(1382400 + 1000);
+        let second_release = mock_env().block.time.plus_seconds(1382400 + 2000);
+
+        // second release time is not mature yet
+        let mut env = mock_env();
+        env.block.time = first_release;
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ClaimUnbonded {
+            release_at: Some(second_release),
+            limit: None,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(res, ContractError::NoMatureClaim {});
+
+        // claim only the second release time after both are mature
+        let mut env = mock_env();
+        env.block.time = second_release;
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ClaimUnbonded {
+            release_at: Some(second_release),
+            limit: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "200");
+        assert_eq!(res.attributes[2].value, "1");
+        assert_eq!(res.attributes[3].value, "2");
+
+        // claims of the first release time are kept
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::ListClaims {
+                address: "staker1".to_string(),
+                start_after: None,
+                limit: None,
+                status: None,
+            },
+        );
+        let claims: ListClaimsResponse = from_binary(&res.unwrap()).unwrap();
+        assert_eq!(claims.claims.len(), 2);
+        assert!(claims.claims.iter().all(|c| c.release_at == first_release));
+
+        // release time is combined with limit
+        let info = mock_info("staker1", &[]);
+        let msg = ExecuteMsg::ClaimUnbonded {
+            release_at: Some(first_release),
+            limit: Some(1),
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(res.attributes[1].value, "50");
+        assert_eq!(res.attributes[3].value, "1");
+    }
+
+    #[test]
+    pub fn test_pause_and_emergency() {
+        // instantiate
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+        // 1000 rewards per second
+        fund_rewards(deps.as_mut());
+        let bond_msg = |sender: &str, amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: sender.to_string(),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+            })
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("stake_token_address", &[]),
+            bond_msg("staker1", 100),
+        )
+        .unwrap();
+
+        // only admin can pause
+        let msg = ExecuteMsg::SetPause {
+            bonding: Some(true),
+            rewards: None,
+            unbonding: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("staker1", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("stake_token_address", &[]),
+            bond_msg("staker2", 200),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::BondingPaused {});
+
+        let msg = ExecuteMsg::SetPause {
+            bonding: Some(false),
+            rewards: None,
+            unbonding: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("stake_token_address", &[]),
+            bond_msg("staker2", 200),
+        )
+        .unwrap();
+
+        // pause rewards only after 1000 seconds
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+        let msg = ExecuteMsg::SetPause {
+            bonding: None,
+            rewards: Some(true),
+            unbonding: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let res: ConfigResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert!(!res.pause.bonding && res.pause.rewards && !res.pause.unbonding);
+
+        env.block.time = env.block.time.plus_seconds(1000);
+        let msg = ExecuteMsg::ReceiveReward { position_id: None };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("staker1", &[]), msg).unwrap_err();
+        assert_eq!(res, ContractError::RewardsPaused {});
+
+        // rewards are not emitted while paused. Unbonding keeps the rewards of
+        // 1/3 weight before the pause in the emptied position
+        let msg = ExecuteMsg::UnbondStake {
+            amount: None,
+            position_id: 1,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("staker1", &[]), msg).unwrap();
+        assert!(res.messages.is_empty());
+        let res = query_position(deps.as_ref(), env.clone(), 1).unwrap();
+        assert_eq!(res.staked_amount, Uint128::zero());
+        assert_eq!(res.rewards[0].pending_rewards, Uint128::new(333_333));
+
+        // rewards are received after unpausing and the emptied position is removed
+        let msg = ExecuteMsg::SetPause {
+            bonding: None,
+            rewards: Some(false),
+            unbonding: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::ReceiveReward { position_id: None };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("staker1", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "reward_token_address".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "staker1".to_string(),
+                    amount: Uint128::new(333_333),
+                })
+                .unwrap(),
+            })
+        );
+        query_position(deps.as_ref(), env.clone(), 1).unwrap_err();
+
+        // unbonding pause stops claims
+        let msg = ExecuteMsg::SetPause {
+            bonding: None,
+            rewards: None,
+            unbonding: Some(true),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::ForceClaim {
+            release_at: env.block.time.plus_seconds(1382400),
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("staker1", &[]), msg).unwrap_err();
+        assert_eq!(res, ContractError::UnbondingPaused {});
+
+        // emergency withdraw is only possible in emergency mode
+        let msg = ExecuteMsg::EmergencyWithdraw { limit: None };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("staker1", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::NotEmergency {});
+        let emergency = ExecuteMsg::SetEmergency { emergency: true };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("staker1", &[]),
+            emergency.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            emergency,
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("stake_token_address", &[]),
+            bond_msg("staker2", 200),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::BondingPaused {});
+
+        // unbonding tokens are withdrawn without fee
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("staker1", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "stake_token_address".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "staker1".to_string(),
+                    amount: Uint128::new(100),
+                })
+                .unwrap(),
+            })
+        );
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("staker1", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::NothingToWithdraw {});
+
+        // staked tokens are withdrawn without rewards
+        let res = execute(deps.as_mut(), env.clone(), mock_info("staker2", &[]), msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.attributes[2].value, "200");
+        assert_eq!(res.attributes[3].value, "0");
+        let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+        assert_eq!(res.total_staked, Uint128::zero());
+        assert_eq!(res.total_weight, Decimal256::zero());
+    }
+
+    #[test]
+    pub fn test_emergency_withdraw_limit() {
+        // instantiate
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+        // three positions of 100 and two claims of 50
+        for _ in 0..3 {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "staker1".to_string(),
+                amount: Uint128::new(100),
+                msg: to_binary(&ReceiveMsg::Bond { duration_day: 16 }).unwrap(),
+            });
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("stake_token_address", &[]),
+                msg,
+            )
+            .unwrap();
+        }
+        for position_id in [1, 2] {
+            let msg = ExecuteMsg::UnbondStake {
+                amount: Some(Uint128::new(50)),
+                position_id,
+            };
+            execute(deps.as_mut(), env.clone(), mock_info("staker1", &[]), msg).unwrap();
+        }
+        let msg = ExecuteMsg::SetEmergency { emergency: true };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::EmergencyWithdraw { limit: Some(0) };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("staker1", &[]), msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidLimit {});
+
+        // oldest two positions
+        let msg = ExecuteMsg::EmergencyWithdraw { limit: Some(2) };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("staker1", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2].value, "100");
+        assert_eq!(res.attributes[3].value, "0");
+        assert_eq!(res.attributes[5].value, "true");
+        query_position(deps.as_ref(), env.clone(), 2).unwrap_err();
+        query_position(deps.as_ref(), env.clone(), 3).unwrap();
+
+        // last position and the oldest claim
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("staker1", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.attributes[2].value, "100");
+        assert_eq!(res.attributes[3].value, "50");
+        assert_eq!(res.attributes[5].value, "true");
+
+        // last claim
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("staker1", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "stake_token_address".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "staker1".to_string(),
+                    amount: Uint128::new(50),
+                })
+                .unwrap(),
+            })
+        );
+        assert_eq!(res.attributes[5].value, "false");
+
+        let res = execute(deps.as_mut(), env.clone(), mock_info("staker1", &[]), msg).unwrap_err();
+        assert_eq!(res, ContractError::NothingToWithdraw {});
+        let res = query_state(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+        assert_eq!(res.total_staked, Uint128::zero());
+        assert_eq!(res.total_weight, Decimal256::zero());
+        let msg = QueryMsg::AllStakers {
+            start_after: None,
+            limit: None,
+        };
+        let res: AllStakersResponse =
+            from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+        assert!(res.stakers.is_empty());
+    }
+
+    #[test]
+    pub fn test_admin_transfer() {
+        // init
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+
+        // only admin can propose
+        let msg = ExecuteMsg::ProposeNewAdmin {
+            new_admin: "admin2".to_string(),
+            expires: Some(Expiration::AtHeight(env.block.height + 100)),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let res: PendingAdminResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::PendingAdmin {}).unwrap())
+                .unwrap();
+        assert_eq!(
+            res,
+            PendingAdminResponse {
+                pending_admin: Some("admin2".to_string()),
+                expires: Some(Expiration::AtHeight(env.block.height + 100)),
+            }
+        );
+
+        // only pending admin can accept
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            ExecuteMsg::AcceptAdmin {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        // proposal can not be accepted after expiry
+        let mut expired_env = mock_env();
+        expired_env.block.height += 100;
+        let res = execute(
+            deps.as_mut(),
+            expired_env,
+            mock_info("admin2", &[]),
+            ExecuteMsg::AcceptAdmin {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::AdminTransferExpired {});
+
+        // admin can cancel
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            ExecuteMsg::CancelAdminTransfer {},
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin2", &[]),
+            ExecuteMsg::AcceptAdmin {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::NoPendingAdmin {});
+
+        // propose without expiry and accept
+        let msg = ExecuteMsg::ProposeNewAdmin {
+            new_admin: "admin2".to_string(),
+            expires: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin2", &[]),
+            ExecuteMsg::AcceptAdmin {},
+        )
+        .unwrap();
+        let config: ConfigResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(config.admin, "admin2".to_string());
+        let res: PendingAdminResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::PendingAdmin {}).unwrap())
+                .unwrap();
+        assert_eq!(res.pending_admin, None);
+
+        // previous admin lost its permissions
+        let msg = ExecuteMsg::SetEmergency { emergency: true };
+        let res = execute(deps.as_mut(), env, mock_info("creator", &[]), msg).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+    }
+
+    #[test]
+    pub fn test_roles() {
+        // init
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            default_init(),
+        )
+        .unwrap();
+
+        // only admin can grant roles
+        let grant = |role: Role, address: &str| ExecuteMsg::GrantRole {
+            role,
+            address: address.to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            grant(Role::Pauser, "random"),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+        for (role, address) in [
+            (Role::RewardManager, "reward_manager"),
+            (Role::FeeManager, "fee_manager"),
+            (Role::Pauser, "pauser1"),
+            (Role::Pauser, "pauser2"),
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("creator", &[]),
+                grant(role, address),
+            )
+            .unwrap();
+        }
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            grant(Role::Pauser, "pauser1"),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::RoleAlreadyGranted {});
+        let msg = QueryMsg::RoleMembers {
+            role: Role::Pauser,
+            start_after: None,
+            limit: None,
+        };
+        let res: RoleMembersResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(res.members, vec!["pauser1", "pauser2"]);
+
+        // reward manager funds rewards and sets reward rate
+        let info = mock_info("reward_token_address", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "reward_manager".to_string(),
+            amount: Uint128::new(100_000_000),
+            msg: to_binary(&ReceiveMsg::RewardUpdate {
+                reward_end_date: env.block.time.plus_seconds(100_000),
+            })
+            .unwrap(),
+        });
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let msg = ExecuteMsg::SetRewardPerSecond {
+            reward_per_second: Uint128::new(500),
+            reward_token: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("fee_manager", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("reward_manager", &[]),
+            msg,
+        )
+        .unwrap();
+
+        // fee manager updates fee settings
+        let msg = ExecuteMsg::UpdateConfig {
+            fee_collector: Some("fee_collector2".to_string()),
+            force_claim_ratio: Some(Decimal::percent(20)),
+            force_claim_curve: None,
+            fee_pool_ratio: None,
+            fee_burn_ratio: None,
+            max_bond_duration: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("reward_manager", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("fee_manager", &[]),
+            msg,
+        )
+        .unwrap();
+
+        // pauser can pause but not unpause
+        let pause = |flag: bool| ExecuteMsg::SetPause {
+            bonding: Some(flag),
+            rewards: None,
+            unbonding: None,
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("pauser1", &[]),
+            pause(true),
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("pauser1", &[]),
+            pause(false),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("fee_manager", &[]),
+            pause(true),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            pause(false),
+        )
+        .unwrap();
+
+        // revoked role loses its permissions
+        let msg = ExecuteMsg::RevokeRole {
+            role: Role::Pauser,
+            address: "pauser1".to_string(),
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap_err();
+        assert_eq!(res, ContractError::RoleNotGranted {});
+        let res = execute(deps.as_mut(), env, mock_info("pauser1", &[]), pause(true)).unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+    }
+
+    #[test]
+    pub fn test_timelocked_changes() {
+        // init with one day timelock
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let mut init_msg = default_init();
+        init_msg.timelock_delay = Some(86400);
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            init_msg,
+        )
+        .unwrap();
+        // 1000 rewards per second
+        fund_rewards(deps.as_mut());
+
+        // funding can not lower the rate without the timelock
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "creator".to_string(),
+            amount: Uint128::new(1000),
+            msg: to_binary(&ReceiveMsg::RewardUpdate {
+                reward_end_date: env.block.time.plus_seconds(1_000_000),
+            })
+            .unwrap(),
+        });
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("reward_token_address", &[]),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::RewardRateLowered {});
+
+        // changes are queued
+        let msg = ExecuteMsg::UpdateConfig {
+            fee_collector: None,
+            force_claim_ratio: Some(Decimal::percent(50)),
+            force_claim_curve: None,
+            fee_pool_ratio: None,
+            fee_burn_ratio: None,
+            max_bond_duration: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.attributes[0].value, "queue_change");
+        assert_eq!(res.attributes[1].value, "1");
+        let msg = ExecuteMsg::SetRewardPerSecond {
+            reward_per_second: Uint128::new(2000),
+            reward_token: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        // invalid changes are rejected when queued
+        let msg = ExecuteMsg::UpdateConfig {
+            fee_collector: None,
+            force_claim_ratio: None,
+            force_claim_curve: None,
+            fee_pool_ratio: Some(Decimal::percent(150)),
+            fee_burn_ratio: None,
+            max_bond_duration: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap_err();
+        assert_eq!(res, ContractError::InvalidFeePoolRatio {});
+
+        // nothing is changed until the delay passes
+        let config: ConfigResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(config.force_claim_ratio, Decimal::percent(10).to_string());
+        let msg = QueryMsg::PendingChanges {
+            start_after: None,
+            limit: None,
+        };
+        let res: PendingChangesResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(res.changes.len(), 2);
+        assert_eq!(res.changes[0].change_id, 1);
+        assert_eq!(
+            res.changes[1].executable_at,
+            env.block.time.plus_seconds(86400)
+        );
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            ExecuteMsg::ExecuteChange { change_id: 1 },
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::ChangeNotExecutable {
+                executable_at: env.block.time.plus_seconds(86400)
+            }
+        );
+
+        // only admin can cancel
+        let msg = ExecuteMsg::CancelChange { change_id: 2 };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+        execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        // anyone can execute after the delay
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(86400);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            ExecuteMsg::ExecuteChange { change_id: 1 },
+        )
+        .unwrap();
+        let config: ConfigResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+        assert_eq!(config.force_claim_ratio, Decimal::percent(50).to_string());
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            ExecuteMsg::ExecuteChange { change_id: 2 },
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::ChangeNotFound {});
+        let res = query_state(deps.as_ref(), env.clone(), QueryMsg::State {}).unwrap();
+        assert_eq!(res.rewards[0].reward_per_second, Uint128::new(1000));
+
+        // timelock delay change is queued with the current delay
+        let msg = ExecuteMsg::SetTimelockDelay { timelock_delay: 0 };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.attributes[0].value, "queue_change");
+        env.block.time = env.block.time.plus_seconds(86400);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            ExecuteMsg::ExecuteChange { change_id: 3 },
+        )
+        .unwrap();
+
+        // changes are applied immediately without delay
+        let msg = ExecuteMsg::SetRewardPerSecond {
+            reward_per_second: Uint128::new(2000),
+            reward_token: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.attributes[0].value, "set_reward_per_second");
+        let res = query_state(deps.as_ref(), env, QueryMsg::State {}).unwrap();
+        assert_eq!(res.rewards[0].reward_per_second, Uint128::new(2000));
     }
 }